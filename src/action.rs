@@ -1,10 +1,87 @@
-use crate::GameID;
+use crate::{
+    bot::Difficulty,
+    coin_flip::{Commitment, Secret},
+    config::Config,
+    state::{GameID, Rules},
+    tournament::TournamentID,
+};
 use codec::{Decode, Encode};
 use gstd::{prelude::*, ActorId};
 
-#[derive(Debug, Encode, Decode, TypeInfo)]
+/// Stake in tokens of fungible token `program`,
+/// which should be approved to the game program before entering the game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct TokenStake {
+    pub program: ActorId,
+    pub amount: u128,
+}
+
+#[derive(Debug, Clone, Encode, Decode, TypeInfo)]
 pub enum Action {
-    Create(ActorId),
+    Create {
+        opponent: Option<ActorId>,
+        rules: Rules,
+        bot: Option<Difficulty>,
+        /// Stake in tokens instead of attached value.
+        token: Option<TokenStake>,
+    },
+    Accept(GameID),
+    Decline(GameID),
+    Join(GameID),
     Cancel(GameID),
-    Turn { id: GameID, x: u64, y: u64 },
+    Resign(GameID),
+    OfferDraw(GameID),
+    AcceptDraw(GameID),
+    DeclineDraw(GameID),
+    RequestTakeback(GameID),
+    ApproveTakeback(GameID),
+    Turn {
+        id: GameID,
+        x: u64,
+        y: u64,
+    },
+    /// Turn in ultimate game to `cell` of sub-`board`, both indexed in row-major order.
+    UltimateTurn {
+        id: GameID,
+        board: u64,
+        cell: u64,
+    },
+    ClaimTimeout(GameID),
+    /// Subscribe to events of the game.
+    Watch(GameID),
+    Unwatch(GameID),
+    /// Remove the watcher from the game, so it can't watch it again.
+    KickWatcher {
+        id: GameID,
+        watcher: ActorId,
+    },
+    /// Cancel up to `limit` expired invites and archive up to `limit`
+    /// games ended before the retention period.
+    Prune {
+        limit: u32,
+    },
+    UpdateConfig(Config),
+    /// Send collected fees to the treasury.
+    WithdrawFees,
+    /// Send fees collected in `token` to the treasury.
+    WithdrawTokenFees(ActorId),
+    /// Retry token payouts of the sender, which were rejected by `token` program.
+    ClaimPayout(ActorId),
+    /// Stop creation of new games and tournaments.
+    Pause,
+    Unpause,
+    CommitFlip {
+        id: GameID,
+        commitment: Commitment,
+    },
+    RevealFlip {
+        id: GameID,
+        secret: Secret,
+    },
+    CreateTournament {
+        rules: Rules,
+        max_players: u32,
+    },
+    RegisterForTournament(TournamentID),
+    StartTournament(TournamentID),
 }
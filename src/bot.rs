@@ -0,0 +1,405 @@
+use crate::state::{BoardMark, Game, DIRECTIONS};
+use codec::{Decode, Encode};
+use gstd::prelude::*;
+
+/// Upper bound of positions evaluated by `Difficulty::Hard` per move,
+/// its own moves are always looked at.
+pub const MINIMAX_MAX_NODES: u64 = 50_000;
+
+const WIN_SCORE: i32 = 1_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum Difficulty {
+    /// Random legal moves.
+    Easy,
+    /// Completes own line or blocks the opponent one, otherwise random.
+    /// Avoids completing own line in misère game.
+    Medium,
+    /// Minimax as deep as `MINIMAX_MAX_NODES` allows, cells next to
+    /// own and opponent marks are preferred among equal scores.
+    Hard,
+}
+
+/// SplitMix64 step, good enough to pick a move.
+fn random(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+impl Game {
+    pub fn get_empty_cells(&self) -> Vec<(usize, usize)> {
+        let mut cells = Vec::new();
+
+        for (y, y_axis) in self.board.iter().enumerate() {
+            for (x, x_axis) in y_axis.iter().enumerate() {
+                if x_axis.is_none() {
+                    cells.push((x, y));
+                }
+            }
+        }
+
+        cells
+    }
+
+    /// Returns (`x`, `y`) move for the current `next_turn` mark.
+    ///
+    /// `seed` is used by random moves and ties only.
+    pub fn get_bot_move(&self, difficulty: Difficulty, seed: u64) -> (usize, usize) {
+        let cells = self.get_empty_cells();
+        if cells.is_empty() {
+            panic!("Board is filled!");
+        }
+
+        let (_, mark) = &self.next_turn;
+        let random_cell = cells[(random(seed) % cells.len() as u64) as usize];
+
+        match difficulty {
+            Difficulty::Easy => random_cell,
+            Difficulty::Medium if self.misere => {
+                let safe_cells: Vec<(usize, usize)> = cells
+                    .iter()
+                    .copied()
+                    .filter(|&cell| self.find_winning_cell(&[cell], mark).is_none())
+                    .collect();
+
+                if safe_cells.is_empty() {
+                    random_cell
+                } else {
+                    safe_cells[(random(seed) % safe_cells.len() as u64) as usize]
+                }
+            }
+            Difficulty::Medium => self
+                .find_winning_cell(&cells, mark)
+                .or_else(|| self.find_winning_cell(&cells, &mark.opposite()))
+                .unwrap_or(random_cell),
+            Difficulty::Hard => self.find_minimax_cell(&cells, mark, seed),
+        }
+    }
+
+    fn find_winning_cell(
+        &self,
+        cells: &[(usize, usize)],
+        mark: &BoardMark,
+    ) -> Option<(usize, usize)> {
+        let mut game = self.clone();
+
+        cells.iter().copied().find(|&(x, y)| {
+            game.board[y][x] = Some(mark.clone());
+            let is_winning = game.is_winning_cell(x, y);
+            game.board[y][x] = None;
+
+            is_winning
+        })
+    }
+
+    fn find_minimax_cell(
+        &self,
+        cells: &[(usize, usize)],
+        mark: &BoardMark,
+        seed: u64,
+    ) -> (usize, usize) {
+        // Search as deep as `MINIMAX_MAX_NODES` allows, full depth on small boards
+        let mut depth = 1;
+        let mut nodes = cells.len() as u64;
+        while depth < cells.len() {
+            nodes = nodes.saturating_mul((cells.len() - depth) as u64);
+            if nodes > MINIMAX_MAX_NODES {
+                break;
+            }
+
+            depth += 1;
+        }
+
+        // Opponent reply isn't searched, so block its line explicitly
+        if depth < 2 && !self.misere {
+            if let Some(cell) = self
+                .find_winning_cell(cells, mark)
+                .or_else(|| self.find_winning_cell(cells, &mark.opposite()))
+            {
+                return cell;
+            }
+        }
+
+        let mut game = self.clone();
+        let mut best_cells = Vec::new();
+        let mut best_score = -WIN_SCORE * 2;
+
+        for &(x, y) in cells {
+            // Window is one below the best score to get exact score of equal cells
+            let score = game.score_cell(x, y, mark, depth, best_score - 1, WIN_SCORE * 2);
+            if score > best_score {
+                best_score = score;
+                best_cells.clear();
+            }
+
+            if score == best_score {
+                best_cells.push((x, y));
+            }
+        }
+
+        // Unfinished search scores most cells equally, so prefer cells next to marks
+        if !self.misere {
+            let best_potential = best_cells
+                .iter()
+                .map(|&(x, y)| self.get_cell_potential(x, y))
+                .max()
+                .unwrap_or_default();
+            best_cells.retain(|&(x, y)| self.get_cell_potential(x, y) == best_potential);
+        }
+
+        best_cells[(random(seed) % best_cells.len() as u64) as usize]
+    }
+
+    /// Returns number of own and opponent marks in lines going through
+    /// (`x`, `y`) cell, i.e. how much the cell builds or blocks lines.
+    fn get_cell_potential(&self, x: usize, y: usize) -> usize {
+        [BoardMark::X, BoardMark::O]
+            .iter()
+            .map(|mark| {
+                DIRECTIONS
+                    .iter()
+                    .map(|&(dx, dy)| {
+                        self.count_marks(x, y, dx, dy, mark)
+                            + self.count_marks(x, y, -dx, -dy, mark)
+                    })
+                    .sum::<usize>()
+            })
+            .sum()
+    }
+
+    /// Returns score of placing `mark` at (`x`, `y`) cell from `mark` point of view.
+    fn score_cell(
+        &mut self,
+        x: usize,
+        y: usize,
+        mark: &BoardMark,
+        depth: usize,
+        alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        self.board[y][x] = Some(mark.clone());
+
+        let score = if self.is_winning_cell(x, y) {
+            // Prefer faster wins and slower losses
+            let score = WIN_SCORE + depth as i32;
+            if self.misere {
+                -score
+            } else {
+                score
+            }
+        } else {
+            -self.negamax(&mark.opposite(), depth - 1, -beta, -alpha)
+        };
+
+        self.board[y][x] = None;
+        score
+    }
+
+    /// Negamax with alpha-beta pruning, scored from `mark` point of view.
+    fn negamax(&mut self, mark: &BoardMark, depth: usize, mut alpha: i32, beta: i32) -> i32 {
+        if depth == 0 {
+            return 0;
+        }
+
+        let cells = self.get_empty_cells();
+        if cells.is_empty() {
+            return 0;
+        }
+
+        let mut best_score = -WIN_SCORE * 2;
+
+        for (x, y) in cells {
+            let score = self.score_cell(x, y, mark, depth, alpha, beta);
+
+            best_score = best_score.max(score);
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        best_score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::Rules;
+    use gstd::ActorId;
+
+    fn setup() -> (ActorId, ActorId, Game) {
+        let player_0 = ActorId::new([0u8; 32]);
+        let bot = ActorId::new([1u8; 32]);
+
+        (
+            player_0,
+            bot,
+            Game::init_vs_bot(player_0, bot, Difficulty::Hard, Rules::default(), 0).unwrap(),
+        )
+    }
+
+    #[test]
+    fn success_easy_move() {
+        let (player_0, bot, mut game) = setup();
+
+        game.turn(&player_0, 1, 1, 0).unwrap();
+
+        for seed in 0..16 {
+            let (x, y) = game.get_bot_move(Difficulty::Easy, seed);
+            assert!(game.board[y][x].is_none());
+        }
+
+        let (x, y) = game.get_bot_move(Difficulty::Easy, 0);
+        assert!(!game.turn(&bot, x, y, 0).unwrap());
+    }
+
+    #[test]
+    fn success_medium_move() {
+        let (player_0, bot, mut game) = setup();
+
+        game.turn(&player_0, 0, 0, 0).unwrap();
+        game.turn(&bot, 2, 0, 0).unwrap();
+        game.turn(&player_0, 0, 1, 0).unwrap();
+
+        // Block
+        assert_eq!(game.get_bot_move(Difficulty::Medium, 0), (0, 2));
+
+        game.turn(&bot, 2, 1, 0).unwrap();
+        game.turn(&player_0, 1, 1, 0).unwrap();
+
+        // Win
+        assert_eq!(game.get_bot_move(Difficulty::Medium, 0), (2, 2));
+    }
+
+    #[test]
+    fn success_hard_move() {
+        let (player_0, bot, mut game) = setup();
+
+        game.turn(&player_0, 0, 0, 0).unwrap();
+
+        // Only the center doesn't lose against corner opening
+        assert_eq!(game.get_bot_move(Difficulty::Hard, 0), (1, 1));
+
+        game.turn(&bot, 1, 1, 0).unwrap();
+        game.turn(&player_0, 2, 2, 0).unwrap();
+
+        let (x, y) = game.get_bot_move(Difficulty::Hard, 0);
+        assert!(x == 1 || y == 1);
+    }
+
+    #[test]
+    fn success_misere_moves() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let bot = ActorId::new([1u8; 32]);
+        let rules = Rules {
+            misere: true,
+            ..Default::default()
+        };
+        let mut game = Game::init_vs_bot(player_0, bot, Difficulty::Hard, rules, 0).unwrap();
+
+        game.turn(&player_0, 1, 1, 0).unwrap();
+        game.turn(&bot, 0, 0, 0).unwrap();
+        game.turn(&player_0, 2, 2, 0).unwrap();
+        game.turn(&bot, 0, 1, 0).unwrap();
+        game.turn(&player_0, 1, 2, 0).unwrap();
+
+        // Both bots don't complete own column
+        for difficulty in [Difficulty::Medium, Difficulty::Hard] {
+            for seed in 0..8 {
+                assert_ne!(game.get_bot_move(difficulty, seed), (0, 2));
+            }
+        }
+    }
+
+    #[test]
+    fn success_hard_never_loses() {
+        // Easy player with different seeds against hard bot
+        for seed in 0..8 {
+            let (player_0, bot, mut game) = setup();
+
+            loop {
+                let (x, y) = game.get_bot_move(Difficulty::Easy, seed);
+                if game.turn(&player_0, x, y, 0).unwrap() {
+                    break;
+                }
+
+                let (x, y) = game.get_bot_move(Difficulty::Hard, seed);
+                if game.turn(&bot, x, y, 0).unwrap() {
+                    break;
+                }
+            }
+
+            assert_ne!(game.get_winner(), Some(player_0));
+        }
+    }
+
+    #[test]
+    fn success_hard_move_large_board() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let bot = ActorId::new([1u8; 32]);
+        let rules = Rules {
+            width: 15,
+            height: 15,
+            win_length: 5,
+            ..Default::default()
+        };
+        let mut game = Game::init_vs_bot(player_0, bot, Difficulty::Hard, rules, 0).unwrap();
+
+        for x in 0..4 {
+            game.turn(&player_0, x, 0, 0).unwrap();
+            game.turn(&bot, x, 5, 0).unwrap();
+        }
+
+        game.turn(&player_0, 10, 10, 0).unwrap();
+
+        // Win instead of block
+        assert_eq!(game.get_bot_move(Difficulty::Hard, 0), (4, 5));
+    }
+
+    #[test]
+    fn success_hard_move_unfinished_search() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let bot = ActorId::new([1u8; 32]);
+        let rules = Rules {
+            width: 15,
+            height: 15,
+            win_length: 5,
+            ..Default::default()
+        };
+        let mut game = Game::init_vs_bot(player_0, bot, Difficulty::Hard, rules, 0).unwrap();
+
+        game.turn(&player_0, 7, 7, 0).unwrap();
+
+        // Search doesn't reach the end, but the bot still plays next to the mark
+        for seed in 0..4 {
+            let (x, y) = game.get_bot_move(Difficulty::Hard, seed);
+            assert!(x.abs_diff(7) <= 1 && y.abs_diff(7) <= 1);
+        }
+    }
+
+    #[test]
+    fn success_hard_move_max_board() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let bot = ActorId::new([1u8; 32]);
+        let rules = Rules {
+            width: 32,
+            height: 32,
+            win_length: 5,
+            ..Default::default()
+        };
+        let mut game = Game::init_vs_bot(player_0, bot, Difficulty::Hard, rules, 0).unwrap();
+
+        for x in 0..4 {
+            game.turn(&player_0, x, 0, 0).unwrap();
+            if x < 3 {
+                game.turn(&bot, 20 + x, 20, 0).unwrap();
+            }
+        }
+
+        // Only own moves are searched, the opponent line is still blocked
+        assert_eq!(game.get_bot_move(Difficulty::Hard, 0), (4, 0));
+    }
+}
@@ -0,0 +1,166 @@
+use crate::error::TicTacToeError;
+use codec::{Decode, Encode};
+use gstd::{prelude::*, ActorId};
+use sha2::{Digest, Sha256};
+
+pub type Secret = [u8; 32];
+pub type Commitment = [u8; 32];
+
+/// Returns SHA-256 commitment to `player` secret.
+///
+/// It's bound to the player, so the opponent can't copy it.
+pub fn commitment(player: &ActorId, secret: &Secret) -> Commitment {
+    Sha256::new()
+        .chain_update(player.as_ref())
+        .chain_update(secret)
+        .finalize()
+        .into()
+}
+
+/// Commit-reveal coin flip, where each player commits to a secret
+/// and reveals it only after the opponent commitment is known.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct CoinFlip {
+    pub commitments: BTreeMap<ActorId, Commitment>,
+    pub secrets: BTreeMap<ActorId, Secret>,
+}
+
+impl CoinFlip {
+    pub fn commit(
+        &mut self,
+        player: &ActorId,
+        commitment: Commitment,
+    ) -> Result<(), TicTacToeError> {
+        if self.commitments.contains_key(player) {
+            return Err(TicTacToeError::AlreadyCommitted);
+        }
+
+        self.commitments.insert(*player, commitment);
+        Ok(())
+    }
+
+    pub fn reveal(&mut self, player: &ActorId, secret: Secret) -> Result<(), TicTacToeError> {
+        if self.commitments.len() < 2 {
+            return Err(TicTacToeError::CommitmentsNotFinished);
+        }
+
+        if self.secrets.contains_key(player) {
+            return Err(TicTacToeError::AlreadyRevealed);
+        }
+
+        if self.commitments.get(player) != Some(&commitment(player, &secret)) {
+            return Err(TicTacToeError::InvalidSecret);
+        }
+
+        self.secrets.insert(*player, secret);
+        Ok(())
+    }
+
+    /// Returns number of protocol steps made by `player`.
+    pub fn progress(&self, player: &ActorId) -> u8 {
+        self.commitments.contains_key(player) as u8 + self.secrets.contains_key(player) as u8
+    }
+
+    /// Returns the flip winner, when both secrets are revealed.
+    ///
+    /// Parity of combined secrets is random if any of them is.
+    pub fn get_winner(&self, player_0: ActorId, player_1: ActorId) -> Option<ActorId> {
+        if self.secrets.len() < 2 {
+            return None;
+        }
+
+        let parity = self
+            .secrets
+            .values()
+            .fold(0, |parity, secret| parity ^ secret[31])
+            & 1;
+
+        Some(if parity == 0 { player_0 } else { player_1 })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> (ActorId, ActorId, CoinFlip) {
+        let player_0 = ActorId::new([0u8; 32]);
+        let player_1 = ActorId::new([1u8; 32]);
+
+        (player_0, player_1, CoinFlip::default())
+    }
+
+    #[test]
+    fn success_coin_flip() {
+        let (player_0, player_1, mut coin_flip) = setup();
+        let (secret_0, secret_1) = ([7u8; 32], [8u8; 32]);
+
+        coin_flip
+            .commit(&player_0, commitment(&player_0, &secret_0))
+            .unwrap();
+        assert_eq!(coin_flip.progress(&player_0), 1);
+        coin_flip
+            .commit(&player_1, commitment(&player_1, &secret_1))
+            .unwrap();
+
+        coin_flip.reveal(&player_1, secret_1).unwrap();
+        assert_eq!(coin_flip.progress(&player_1), 2);
+        assert_eq!(coin_flip.get_winner(player_0, player_1), None);
+
+        coin_flip.reveal(&player_0, secret_0).unwrap();
+        assert_eq!(coin_flip.get_winner(player_0, player_1), Some(player_1));
+    }
+
+    #[test]
+    fn success_commitment_bound_to_player() {
+        let (player_0, player_1, _) = setup();
+        let secret = [7u8; 32];
+
+        assert_ne!(
+            commitment(&player_0, &secret),
+            commitment(&player_1, &secret)
+        );
+    }
+
+    #[test]
+    fn error_reveal_before_commitments() {
+        let (player_0, _, mut coin_flip) = setup();
+        let secret = [7u8; 32];
+
+        coin_flip
+            .commit(&player_0, commitment(&player_0, &secret))
+            .unwrap();
+        assert_eq!(
+            coin_flip.reveal(&player_0, secret),
+            Err(TicTacToeError::CommitmentsNotFinished)
+        );
+    }
+
+    #[test]
+    fn error_reveal_invalid_secret() {
+        let (player_0, player_1, mut coin_flip) = setup();
+        let secret = [7u8; 32];
+
+        coin_flip
+            .commit(&player_0, commitment(&player_0, &secret))
+            .unwrap();
+        coin_flip
+            .commit(&player_1, commitment(&player_1, &secret))
+            .unwrap();
+        assert_eq!(
+            coin_flip.reveal(&player_0, [8u8; 32]),
+            Err(TicTacToeError::InvalidSecret)
+        );
+    }
+
+    #[test]
+    fn error_commit_twice() {
+        let (player_0, _, mut coin_flip) = setup();
+
+        coin_flip.commit(&player_0, [0u8; 32]).unwrap();
+        assert_eq!(
+            coin_flip.commit(&player_0, [0u8; 32]),
+            Err(TicTacToeError::AlreadyCommitted)
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use crate::{error::TicTacToeError, state::DEFAULT_MOVE_TIMEOUT};
+use codec::{Decode, Encode};
+use gstd::{prelude::*, ActorId};
+
+/// Basis points of the whole pot.
+const BPS: u128 = 10_000;
+
+/// Highest platform fee, 10% of the pot.
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+/// Returns `fee_bps` basis points of `amount`, rounded down.
+pub fn calculate_fee(amount: u128, fee_bps: u16) -> u128 {
+    let fee_bps = fee_bps as u128;
+
+    // Split to avoid overflow on huge amounts
+    amount / BPS * fee_bps + amount % BPS * fee_bps / BPS
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct Config {
+    /// Used by games created with zero `Rules::move_timeout`.
+    pub default_move_timeout: u32,
+    /// Maximum number of pending games created by one player, zero means no limit.
+    pub max_open_games: u32,
+    pub bots_enabled: bool,
+    pub tournaments_enabled: bool,
+    pub wagers_enabled: bool,
+    /// Platform fee taken from the pot of finished game in basis points.
+    pub fee_bps: u16,
+    /// Account which can withdraw collected fees.
+    pub treasury: ActorId,
+    /// NFT program which mints a trophy to the winner of each game.
+    pub trophy_nft: Option<ActorId>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            default_move_timeout: DEFAULT_MOVE_TIMEOUT,
+            max_open_games: 0,
+            bots_enabled: true,
+            tournaments_enabled: true,
+            wagers_enabled: true,
+            fee_bps: 0,
+            treasury: ActorId::zero(),
+            trophy_nft: None,
+        }
+    }
+}
+
+impl Config {
+    pub fn validate(&self) -> Result<(), TicTacToeError> {
+        if self.default_move_timeout == 0 {
+            return Err(TicTacToeError::InvalidMoveTimeout);
+        }
+
+        if self.fee_bps > MAX_FEE_BPS {
+            return Err(TicTacToeError::InvalidFee);
+        }
+
+        // Otherwise collected fees can't be withdrawn
+        if self.fee_bps != 0 && self.treasury == ActorId::zero() {
+            return Err(TicTacToeError::NoTreasury);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct InitConfig {
+    pub owner: ActorId,
+    pub config: Config,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn success_calculate_fee() {
+        assert_eq!(calculate_fee(200, 250), 5);
+        assert_eq!(calculate_fee(199, 250), 4);
+        assert_eq!(calculate_fee(200, 0), 0);
+        assert_eq!(calculate_fee(200, MAX_FEE_BPS), 20);
+        assert_eq!(calculate_fee(u128::MAX, 10_000), u128::MAX);
+    }
+
+    #[test]
+    fn error_invalid_fee() {
+        let config = Config {
+            fee_bps: MAX_FEE_BPS + 1,
+            treasury: ActorId::new([1u8; 32]),
+            ..Default::default()
+        };
+
+        assert_eq!(config.validate(), Err(TicTacToeError::InvalidFee));
+    }
+
+    #[test]
+    fn error_fee_without_treasury() {
+        let config = Config {
+            fee_bps: 250,
+            ..Default::default()
+        };
+
+        assert_eq!(config.validate(), Err(TicTacToeError::NoTreasury));
+    }
+}
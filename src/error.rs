@@ -0,0 +1,91 @@
+use codec::{Decode, Encode};
+use gstd::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum TicTacToeError {
+    GameNotFound,
+    TournamentNotFound,
+    /// Player can't play against themself.
+    SamePlayers,
+    InvalidBoardSize,
+    InvalidWinLength,
+    InvalidMoveTimeout,
+    /// Attached value doesn't match the game stake.
+    InvalidStake,
+    BotGameWithOpponentOrStake,
+    GameNotPending,
+    GameNotOpen,
+    GameNotStarted,
+    GameEnded,
+    InviteExpired,
+    InviteNotAddressedToYou,
+    PlayerNotInGame,
+    NotYourTurn,
+    /// Only the waiting player can claim a timeout.
+    YourTurn,
+    MoveDeadlineNotPassed,
+    OutOfBoard,
+    LocationNotEmpty,
+    TournamentGameCantBeCanceled,
+    InvalidMaxPlayers,
+    RegistrationClosed,
+    AlreadyRegistered,
+    TournamentFull,
+    NotTournamentCreator,
+    NotEnoughPlayers,
+    BotGameWithCoinFlip,
+    /// Coin flip is disabled or already finished.
+    NoCoinFlip,
+    /// First turn isn't decided by the coin flip yet.
+    CoinFlipNotFinished,
+    AlreadyCommitted,
+    /// Secrets are revealed after both players commit.
+    CommitmentsNotFinished,
+    AlreadyRevealed,
+    /// Secret doesn't match the commitment.
+    InvalidSecret,
+    /// Coin flip timeout can be claimed only by the player ahead in the protocol.
+    CoinFlipNotAhead,
+    CancelAlreadyOffered,
+    DrawAlreadyOffered,
+    /// There is no draw offer from the opponent.
+    NoDrawOffer,
+    TakebackInTournament,
+    /// Only the player who made the last move can ask to undo it.
+    NoMoveToTakeBack,
+    TakebackAlreadyRequested,
+    /// There is no takeback request from the opponent.
+    NoTakebackRequest,
+    NotOwner,
+    /// New games can't be created while the program is paused.
+    Paused,
+    FeatureDisabled,
+    /// Player has reached `Config::max_open_games`.
+    TooManyOpenGames,
+    /// Fee is more than `MAX_FEE_BPS`.
+    InvalidFee,
+    NotTreasury,
+    /// Fee is set without the treasury to withdraw it.
+    NoTreasury,
+    /// Token deposit of another player is being transferred to the game.
+    DepositInProgress,
+    TokenTransferFailed,
+    NoUnpaidPayout,
+    AlreadyWatching,
+    NotWatching,
+    /// Game has reached `MAX_WATCHERS`.
+    TooManyWatchers,
+    /// Watcher was removed from the game by its player.
+    KickedWatcher,
+    /// Ultimate game is played on 3x3 sub-boards with `win_length` 3.
+    InvalidUltimateRules,
+    BotGameWithUltimate,
+    TakebackInUltimate,
+    /// `Action::UltimateTurn` is required in ultimate game.
+    UltimateTurnRequired,
+    NotUltimateGame,
+    /// Sub-board is closed or doesn't match the previous move cell.
+    InvalidSubBoard,
+    /// Tournament is larger than `MAX_TOURNAMENT_PLAYERS`.
+    TooManyTournamentPlayers,
+}
@@ -1,4 +1,4 @@
-use crate::GameID;
+use crate::{config::Config, error::TicTacToeError, tournament::TournamentID, GameID};
 use codec::{Decode, Encode};
 use gstd::{prelude::*, ActorId};
 
@@ -7,17 +7,127 @@ pub enum Event {
     Created {
         id: GameID,
         player_0: ActorId,
-        player_1: ActorId,
+        player_1: Option<ActorId>,
+    },
+    Accepted(GameID),
+    Declined(GameID),
+    Joined {
+        id: GameID,
+        player: ActorId,
     },
     Canceled(GameID),
+    /// Opponent has to cancel the started game too.
+    CancelOffered {
+        id: GameID,
+        player: ActorId,
+    },
     NewTurn {
         id: GameID,
         x: u64,
         y: u64,
         player: ActorId,
     },
+    NewUltimateTurn {
+        id: GameID,
+        board: u64,
+        cell: u64,
+        player: ActorId,
+    },
     Finished {
         id: GameID,
         winner: Option<ActorId>,
     },
+    /// `net` amount is sent after `fee` is taken from `gross` amount,
+    /// either in value or in tokens of `token` program.
+    Payout {
+        id: GameID,
+        to: ActorId,
+        token: Option<ActorId>,
+        gross: u128,
+        fee: u128,
+        net: u128,
+    },
+    /// Token transfer was rejected, `amount` can be claimed later.
+    PayoutFailed {
+        id: GameID,
+        to: ActorId,
+        token: ActorId,
+        amount: u128,
+    },
+    PayoutClaimed {
+        to: ActorId,
+        token: ActorId,
+        amount: u128,
+    },
+    RatingChanged {
+        player: ActorId,
+        old_rating: u32,
+        new_rating: u32,
+    },
+    TournamentCreated(TournamentID),
+    RegisteredForTournament {
+        id: TournamentID,
+        player: ActorId,
+    },
+    TournamentStarted(TournamentID),
+    TournamentFinished {
+        id: TournamentID,
+        winner: ActorId,
+    },
+    FlipCommitted {
+        id: GameID,
+        player: ActorId,
+    },
+    FlipRevealed {
+        id: GameID,
+        player: ActorId,
+    },
+    /// Coin flip winner plays `BoardMark::X`.
+    FirstTurnDecided {
+        id: GameID,
+        player: ActorId,
+    },
+    DrawOffered {
+        id: GameID,
+        player: ActorId,
+    },
+    DrawDeclined(GameID),
+    TakebackRequested {
+        id: GameID,
+        player: ActorId,
+    },
+    /// Move at (`x`, `y`) cell is undone.
+    TakebackApplied {
+        id: GameID,
+        x: u64,
+        y: u64,
+        player: ActorId,
+    },
+    Watched {
+        id: GameID,
+        watcher: ActorId,
+    },
+    Unwatched {
+        id: GameID,
+        watcher: ActorId,
+    },
+    WatcherKicked {
+        id: GameID,
+        watcher: ActorId,
+    },
+    /// `expired` invites are canceled, `count` games are archived.
+    Pruned {
+        expired: u32,
+        count: u32,
+    },
+    ConfigUpdated(Config),
+    /// Fees in value if `token` is `None`.
+    FeesWithdrawn {
+        treasury: ActorId,
+        token: Option<ActorId>,
+        amount: u128,
+    },
+    Paused,
+    Unpaused,
+    Error(TicTacToeError),
 }
@@ -0,0 +1,40 @@
+use codec::{Decode, Encode};
+use gstd::{prelude::*, ActorId};
+
+/// Action of the fungible token program used for token stakes.
+///
+/// Variants are declared in the same order as in the token program,
+/// so they are encoded the same way.
+#[derive(Debug, Clone, Encode, Decode, TypeInfo)]
+pub enum FTAction {
+    Mint(u128),
+    Burn(u128),
+    /// Sender should be `from` or approved by it.
+    Transfer {
+        from: ActorId,
+        to: ActorId,
+        amount: u128,
+    },
+    Approve {
+        to: ActorId,
+        amount: u128,
+    },
+    TotalSupply,
+    BalanceOf(ActorId),
+}
+
+#[derive(Debug, Clone, Encode, Decode, TypeInfo)]
+pub enum FTEvent {
+    Transfer {
+        from: ActorId,
+        to: ActorId,
+        amount: u128,
+    },
+    Approve {
+        from: ActorId,
+        to: ActorId,
+        amount: u128,
+    },
+    TotalSupply(u128),
+    Balance(u128),
+}
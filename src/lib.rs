@@ -2,26 +2,505 @@
 #![allow(clippy::missing_safety_doc)]
 
 pub mod action;
+pub mod bot;
+pub mod coin_flip;
+pub mod config;
+pub mod error;
 pub mod event;
+pub mod ft;
+pub mod rating;
 pub mod state;
 pub mod state_query;
+pub mod tournament;
+pub mod trophy;
+pub mod ultimate;
 
 use action::*;
+use config::{Config, InitConfig};
+use error::TicTacToeError;
 use event::*;
-use gstd::{msg, prelude::*};
+use ft::{FTAction, FTEvent};
+use gstd::{exec, msg, prelude::*, ActorId};
+use rating::{Leaderboard, DRAW_SCORE, LOSS_SCORE, WIN_SCORE};
 use state::*;
 use state_query::*;
+use tournament::{Pairing, Tournament, TournamentID};
+use trophy::{NFTAction, TrophyMetadata};
 
 #[derive(Debug, Default)]
 pub struct TicTacToe {
+    pub owner: ActorId,
+    pub config: Config,
+    /// New games and tournaments can't be created while paused.
+    pub paused: bool,
+    /// Total platform fees taken from finished games.
+    pub fees_collected: u128,
+    /// Fees which can be withdrawn by the treasury.
+    pub fees_balance: u128,
+    /// Total platform fees taken from finished games by token.
+    pub token_fees_collected: BTreeMap<ActorId, u128>,
+    /// Fees which can be withdrawn by the treasury by token.
+    pub token_fees_balance: BTreeMap<ActorId, u128>,
     pub games: BTreeMap<GameID, Game>,
     pub nonce: GameID,
+    pub player_to_games: BTreeMap<ActorId, BTreeSet<GameID>>,
+    pub leaderboard: Leaderboard,
+    pub tournaments: BTreeMap<TournamentID, Tournament>,
+    pub tournament_nonce: TournamentID,
+    pub archived_games: BTreeMap<GameID, ArchivedGame>,
+    /// Ended games waiting for archival with their end blocks, oldest first.
+    pub ended_games: VecDeque<(u32, GameID)>,
+    /// Invites with their creation blocks, oldest first, some may be
+    /// already accepted or declined.
+    pub invites: VecDeque<(u32, GameID)>,
+    /// Token payouts rejected by token programs by player and token.
+    pub unpaid_payouts: BTreeMap<(ActorId, ActorId), u128>,
+}
+
+impl TicTacToe {
+    fn assert_owner(&self, player: &ActorId) -> Result<(), TicTacToeError> {
+        if player != &self.owner {
+            return Err(TicTacToeError::NotOwner);
+        }
+
+        Ok(())
+    }
+
+    fn assert_not_paused(&self) -> Result<(), TicTacToeError> {
+        if self.paused {
+            return Err(TicTacToeError::Paused);
+        }
+
+        Ok(())
+    }
+
+    /// Returns number of pending games created by `player`,
+    /// except invites expired at `now` block.
+    fn count_open_games(&self, player: &ActorId, now: u32) -> u32 {
+        let ids = match self.player_to_games.get(player) {
+            Some(ids) => ids,
+            None => return 0,
+        };
+
+        ids.iter()
+            .filter_map(|id| self.games.get(id))
+            .filter(|game| {
+                &game.player_0 == player && game.is_pending() && !game.is_invite_expired(now)
+            })
+            .count() as u32
+    }
+
+    fn get_game_mut(&mut self, id: GameID) -> Result<&mut Game, TicTacToeError> {
+        self.games.get_mut(&id).ok_or(TicTacToeError::GameNotFound)
+    }
+
+    fn get_tournament_mut(&mut self, id: TournamentID) -> Result<&mut Tournament, TicTacToeError> {
+        self.tournaments
+            .get_mut(&id)
+            .ok_or(TicTacToeError::TournamentNotFound)
+    }
+
+    /// Stores new game and indexes it by its players.
+    fn add_game(&mut self, game: Game) -> GameID {
+        self.nonce = self.nonce.checked_add(1).expect("Math overflow!");
+        let id = self.nonce;
+
+        self.add_player_game(game.player_0, id);
+        if let (Some(player_1), None) = (game.player_1, game.bot) {
+            self.add_player_game(player_1, id);
+        }
+
+        self.games.insert(id, game);
+        id
+    }
+
+    /// Settles ended game: mints the winner trophy, updates ratings of finished
+    /// game players, advances the tournament bracket and pays out the pot.
+    async fn on_game_ended(&mut self, id: GameID) {
+        let now = exec::block_height();
+        let game = self.games.get_mut(&id).expect("Game not found!");
+
+        // Deposits of canceled games are refunded in full
+        let fee_bps = match game.status {
+            GameStatus::Finished { winner: _ } => game.fee_bps,
+            _ => 0,
+        };
+        let token = game.token;
+        let payouts = game.settle();
+
+        self.ended_games.push_back((now, id));
+
+        if let (Some(nft), Some(token_metadata)) =
+            (self.config.trophy_nft, TrophyMetadata::new(id, game, now))
+        {
+            let mint = NFTAction::Mint {
+                to: token_metadata.winner,
+                token_metadata,
+            };
+
+            // Failed mint doesn't affect the game result
+            msg::send(nft, mint, 0).ok();
+        }
+
+        // Practice games against the program are not rated
+        if let (GameStatus::Finished { winner }, None) = (&game.status, game.bot) {
+            let winner = *winner;
+            let player_0 = game.player_0;
+            let player_1 = game.player_1.expect("Invalid data");
+            let tournament = game.tournament;
+
+            self.update_ratings(player_0, player_1, winner);
+
+            if let Some(tournament) = tournament {
+                self.advance_tournament(tournament, id, winner);
+            }
+        }
+
+        // Paid last, so the rest of the game is settled while transfers are awaited
+        self.payout(id, payouts, token, fee_bps).await;
+    }
+
+    /// Sends each of ended game `payouts` with corresponding value or
+    /// `token` amount reduced by `fee_bps` platform fee.
+    ///
+    /// Token payout is reported only after the token program confirms it,
+    /// rejected one is kept as unpaid, so the player can claim it later.
+    async fn payout(
+        &mut self,
+        id: GameID,
+        payouts: Vec<(ActorId, u128)>,
+        token: Option<ActorId>,
+        fee_bps: u16,
+    ) {
+        for (to, gross) in payouts {
+            let fee = config::calculate_fee(gross, fee_bps);
+            let net = gross - fee;
+
+            self.add_fee(token, fee);
+
+            let event = Event::Payout {
+                id,
+                to,
+                token,
+                gross,
+                fee,
+                net,
+            };
+
+            let token = match token {
+                Some(token) => token,
+                None => {
+                    msg::send(to, event, net).expect("Unable to send payout!");
+                    continue;
+                }
+            };
+
+            if transfer_tokens(token, exec::program_id(), to, net)
+                .await
+                .is_ok()
+            {
+                msg::send(to, event, 0).expect("Unable to send payout!");
+            } else {
+                let unpaid = self.unpaid_payouts.entry((to, token)).or_default();
+                *unpaid = unpaid.saturating_add(net);
+
+                msg::send(
+                    to,
+                    Event::PayoutFailed {
+                        id,
+                        to,
+                        token,
+                        amount: net,
+                    },
+                    0,
+                )
+                .expect("Unable to send payout!");
+            }
+        }
+    }
+
+    /// Adds `fee` to the fees collected in value or in `token`.
+    fn add_fee(&mut self, token: Option<ActorId>, fee: u128) {
+        match token {
+            Some(token) => {
+                for fees in [&mut self.token_fees_collected, &mut self.token_fees_balance] {
+                    let amount = fees.entry(token).or_default();
+                    *amount = amount.saturating_add(fee);
+                }
+            }
+            None => {
+                self.fees_collected = self.fees_collected.saturating_add(fee);
+                self.fees_balance = self.fees_balance.saturating_add(fee);
+            }
+        }
+    }
+
+    /// Returns unpaid token payouts of `player` by token.
+    pub fn get_unpaid_payouts(&self, player: &ActorId) -> Vec<(ActorId, u128)> {
+        self.unpaid_payouts
+            .iter()
+            .filter(|((to, _), _)| to == player)
+            .map(|((_, token), amount)| (*token, *amount))
+            .collect()
+    }
+
+    fn advance_tournament(&mut self, id: TournamentID, game: GameID, winner: Option<ActorId>) {
+        let tournament = self
+            .tournaments
+            .get_mut(&id)
+            .expect("Tournament not found!");
+        let pairings = tournament.handle_result(game, winner);
+
+        if let Some(winner) = tournament.get_winner() {
+            for player in &tournament.players {
+                msg::send(*player, Event::TournamentFinished { id, winner }, 0)
+                    .expect("Unable to send tournament result!");
+            }
+        }
+
+        self.open_tournament_games(id, pairings);
+    }
+
+    /// Opens started game for each of tournament `pairings`
+    /// and notifies paired players about it.
+    fn open_tournament_games(&mut self, id: TournamentID, pairings: Vec<Pairing>) {
+        let now = exec::block_height();
+
+        for (match_index, player_0, player_1) in pairings {
+            let tournament = self.tournaments.get(&id).expect("Tournament not found!");
+            let game =
+                Game::init_for_tournament(player_0, player_1, tournament.rules.clone(), id, now)
+                    .expect("Invalid data");
+
+            let game_id = self.add_game(game);
+            self.tournaments
+                .get_mut(&id)
+                .expect("Tournament not found!")
+                .set_game(match_index, game_id);
+            schedule_timeout(game_id, &self.games[&game_id]);
+
+            for player in [player_0, player_1] {
+                let event = Event::Created {
+                    id: game_id,
+                    player_0,
+                    player_1: Some(player_1),
+                };
+                msg::send(player, event, 0).expect("Unable to send tournament game!");
+            }
+        }
+    }
+
+    fn update_ratings(&mut self, player_0: ActorId, player_1: ActorId, winner: Option<ActorId>) {
+        let rating_0 = self.get_rating(&player_0);
+        let rating_1 = self.get_rating(&player_1);
+
+        let (score_0, score_1) = match winner {
+            Some(winner) if winner == player_0 => (WIN_SCORE, LOSS_SCORE),
+            Some(_) => (LOSS_SCORE, WIN_SCORE),
+            None => (DRAW_SCORE, DRAW_SCORE),
+        };
+        let (new_rating_0, new_rating_1) = rating::calculate_ratings(rating_0, rating_1, score_0);
+
+        for (player, old_rating, new_rating, score) in [
+            (player_0, rating_0, new_rating_0, score_0),
+            (player_1, rating_1, new_rating_1, score_1),
+        ] {
+            self.leaderboard.record(player, new_rating, score);
+
+            msg::send(
+                player,
+                Event::RatingChanged {
+                    player,
+                    old_rating,
+                    new_rating,
+                },
+                0,
+            )
+            .expect("Unable to send rating change!");
+        }
+    }
+
+    pub fn get_rating(&self, player: &ActorId) -> u32 {
+        self.leaderboard.get_stats(player).rating
+    }
+
+    /// Replaces up to `limit` games ended before the retention
+    /// period at `now` block with their result records.
+    ///
+    /// Returns number of archived games.
+    fn prune(&mut self, now: u32, limit: u32) -> u32 {
+        let mut count = 0;
+
+        while let Some(&(ended_at, id)) = self.ended_games.front() {
+            if count >= limit || now <= ended_at.saturating_add(GAME_RETENTION_BLOCKS) {
+                break;
+            }
+
+            let game = self.games.remove(&id).expect("Invalid data");
+            self.archived_games.insert(id, game.archive(ended_at));
+            self.ended_games.pop_front();
+
+            count += 1;
+        }
+
+        count
+    }
+
+    /// Cancels up to `limit` invites expired at `now` block and refunds
+    /// their creators.
+    ///
+    /// Returns number of canceled invites.
+    async fn expire_invites(&mut self, now: u32, limit: u32) -> u32 {
+        let mut count = 0;
+
+        while let Some(&(_, id)) = self.invites.front() {
+            if count >= limit {
+                break;
+            }
+
+            // Accepted or declined invite may be already archived
+            if let Some(game) = self.games.get_mut(&id).filter(|game| game.is_pending()) {
+                if !game.expire_invite(now) {
+                    break;
+                }
+
+                notify(game, &Event::Canceled(id));
+                self.on_game_ended(id).await;
+                count += 1;
+            }
+
+            self.invites.pop_front();
+        }
+
+        count
+    }
+
+    /// Returns status of the game, which may be archived.
+    fn get_status(&self, id: &GameID) -> Option<&GameStatus> {
+        match self.archived_games.get(id) {
+            Some(archived_game) => Some(&archived_game.status),
+            None => self.games.get(id).map(|game| &game.status),
+        }
+    }
+
+    fn add_player_game(&mut self, player: ActorId, id: GameID) {
+        self.player_to_games.entry(player).or_default().insert(id);
+    }
+
+    /// Returns `player` games page ordered by id.
+    pub fn get_player_games(
+        &self,
+        player: &ActorId,
+        status_filter: Option<StatusFilter>,
+        offset: u64,
+        limit: u64,
+    ) -> Vec<GameID> {
+        let ids = match self.player_to_games.get(player) {
+            Some(ids) => ids,
+            None => return Vec::new(),
+        };
+
+        ids.iter()
+            .filter(|id| match status_filter {
+                Some(status_filter) => {
+                    let status = self.get_status(id).expect("Invalid data");
+                    status_filter.matches(status)
+                }
+                None => true,
+            })
+            .skip(usize::try_from(offset).unwrap_or(usize::MAX))
+            .take(usize::try_from(limit).unwrap_or(usize::MAX))
+            .copied()
+            .collect()
+    }
 }
 
 static mut TIC_TAC_TOE: Option<TicTacToe> = None;
 
+/// Sends delayed `Action::ClaimTimeout` to the program itself,
+/// which finishes stale game after the move deadline.
+fn schedule_timeout(id: GameID, game: &Game) {
+    if game.auto_timeout {
+        msg::send_delayed(
+            exec::program_id(),
+            Action::ClaimTimeout(id),
+            0,
+            game.move_timeout.saturating_add(1),
+        )
+        .expect("Unable to schedule timeout!");
+    }
+}
+
+/// Sends `event` about `game` to its players and watchers,
+/// except the message sender and the program itself.
+fn notify(game: &Game, event: &Event) {
+    let sender = msg::source();
+    let program = exec::program_id();
+
+    for to in game.get_audience() {
+        if to != sender && to != program {
+            // Failed notification doesn't affect the game
+            msg::send(to, event, 0).ok();
+        }
+    }
+}
+
+/// Replies with `event` about `game` and notifies the rest of its audience.
+fn reply_and_notify(game: &Game, event: Event) {
+    msg::reply(&event, 0).unwrap();
+    notify(game, &event);
+}
+
+/// Transfers `amount` of `token` and waits for the token program confirmation.
+async fn transfer_tokens(
+    token: ActorId,
+    from: ActorId,
+    to: ActorId,
+    amount: u128,
+) -> Result<(), TicTacToeError> {
+    let reply =
+        msg::send_for_reply_as::<_, FTEvent>(token, FTAction::Transfer { from, to, amount }, 0)
+            .map_err(|_| TicTacToeError::TokenTransferFailed)?
+            .await
+            .map_err(|_| TicTacToeError::TokenTransferFailed)?;
+
+    // Token program may move a different amount, e.g. with its own fee
+    match reply {
+        FTEvent::Transfer {
+            from: reply_from,
+            to: reply_to,
+            amount: reply_amount,
+        } if (reply_from, reply_to, reply_amount) == (from, to, amount) => Ok(()),
+        _ => Err(TicTacToeError::TokenTransferFailed),
+    }
+}
+
+/// Transfers token stake of `player` entering game `id` to the program,
+/// while the game is locked for other players.
+///
+/// Returns the deposited amount.
+async fn deposit_tokens(
+    ttt: &mut TicTacToe,
+    id: GameID,
+    player: ActorId,
+) -> Result<u128, TicTacToeError> {
+    let game = ttt.get_game_mut(id)?;
+    let token = game.token.expect("Invalid data");
+    let stake = game.stake;
+
+    game.lock_deposit(&player)?;
+    let result = transfer_tokens(token, player, exec::program_id(), stake).await;
+
+    // Pending game can't be removed while it's locked
+    ttt.get_game_mut(id).expect("Invalid data").unlock_deposit();
+
+    result.map(|()| stake)
+}
+
 gstd::metadata! {
     title: "TicTacToe",
+    init:
+        input: InitConfig,
     handle:
         input: Action,
         output: Event,
@@ -32,7 +511,12 @@ gstd::metadata! {
 
 #[no_mangle]
 pub unsafe extern "C" fn init() {
+    let InitConfig { owner, config } = msg::load().expect("Invalid InitConfig data!");
+    config.validate().expect("Invalid config!");
+
     let tic_tac_toe = TicTacToe {
+        owner,
+        config,
         nonce: 0,
         ..Default::default()
     };
@@ -40,110 +524,1381 @@ pub unsafe extern "C" fn init() {
     TIC_TAC_TOE = Some(tic_tac_toe);
 }
 
-#[no_mangle]
-pub unsafe extern "C" fn handle() {
+#[gstd::async_main]
+async fn main() {
     let action: Action = msg::load().expect("Invalid Action data!");
-    let ttt: &mut TicTacToe = TIC_TAC_TOE.get_or_insert(TicTacToe::default());
+    let ttt: &mut TicTacToe = unsafe { TIC_TAC_TOE.get_or_insert(TicTacToe::default()) };
+
+    // Failed action doesn't change the state, except canceling the expired invite
+    // it accessed, so the attached value is returned
+    if let Err(error) = handle_action(ttt, action).await {
+        msg::reply(Event::Error(error), msg::value()).expect("Unable to reply error!");
+    }
+}
+
+/// Replies to successful `action`, which is fully validated before any reply.
+async fn handle_action(ttt: &mut TicTacToe, action: Action) -> Result<(), TicTacToeError> {
+    // Value of other actions isn't tracked, so it's refunded with the error
+    if msg::value() != 0
+        && !matches!(
+            action,
+            Action::Create { .. } | Action::Accept(_) | Action::Join(_)
+        )
+    {
+        return Err(TicTacToeError::InvalidStake);
+    }
 
     match action {
-        Action::Create(opponent) => {
-            ttt.nonce = ttt.nonce.checked_add(1).expect("Math overflow!");
-            let id = ttt.nonce;
+        Action::Create {
+            opponent,
+            mut rules,
+            bot,
+            token,
+        } => {
+            ttt.assert_not_paused()?;
 
             let player_0 = msg::source();
-            let player_1 = opponent;
+            let now = exec::block_height();
+
+            if rules.move_timeout == 0 {
+                rules.move_timeout = ttt.config.default_move_timeout;
+            }
 
-            ttt.games.insert(id, Game::init(player_0, player_1));
+            // Zero token stake is the same as no stake
+            let token = token.filter(|token| token.amount != 0);
+            let stake = match token {
+                Some(_) if msg::value() != 0 => return Err(TicTacToeError::InvalidStake),
+                Some(TokenStake { amount, .. }) => amount,
+                None => msg::value(),
+            };
 
-            msg::reply(
+            if stake != 0 && !ttt.config.wagers_enabled {
+                return Err(TicTacToeError::FeatureDisabled);
+            }
+
+            let game = match bot {
+                Some(difficulty) => {
+                    if !ttt.config.bots_enabled {
+                        return Err(TicTacToeError::FeatureDisabled);
+                    }
+
+                    if opponent.is_some() || stake != 0 {
+                        return Err(TicTacToeError::BotGameWithOpponentOrStake);
+                    }
+
+                    Game::init_vs_bot(player_0, exec::program_id(), difficulty, rules, now)?
+                }
+                None => {
+                    let mut game = Game::init(player_0, opponent, rules, stake, now)?;
+                    game.token = token.map(|token| token.program);
+                    game.fee_bps = ttt.config.fee_bps;
+                    game
+                }
+            };
+
+            let player_1 = game.player_1;
+
+            let max_open_games = ttt.config.max_open_games;
+            if game.is_pending()
+                && max_open_games != 0
+                && ttt.count_open_games(&player_0, now) >= max_open_games
+            {
+                return Err(TicTacToeError::TooManyOpenGames);
+            }
+
+            // Game isn't stored until its stake is received
+            if let Some(TokenStake { program, amount }) = token {
+                transfer_tokens(program, player_0, exec::program_id(), amount).await?;
+            }
+
+            let is_invite = game.is_pending() && player_1.is_some();
+            let id = ttt.add_game(game);
+            if is_invite {
+                ttt.invites.push_back((now, id));
+            }
+
+            let game = &ttt.games[&id];
+
+            if bot.is_some() {
+                schedule_timeout(id, game);
+            }
+
+            // Invited opponent learns about the game
+            reply_and_notify(
+                game,
                 Event::Created {
                     id,
                     player_0,
                     player_1,
                 },
-                0,
-            )
-            .unwrap();
+            );
+            ttt.expire_invites(now, AUTO_PRUNE_LIMIT).await;
+            ttt.prune(now, AUTO_PRUNE_LIMIT);
+        }
+        Action::Accept(id) => {
+            let player = msg::source();
+            let now = exec::block_height();
+            let game = ttt.get_game_mut(id)?;
+
+            // Expired invite is closed on access, refunding its creator
+            if game.player_1 == Some(player) && game.expire_invite(now) {
+                notify(game, &Event::Canceled(id));
+                ttt.on_game_ended(id).await;
+                return Err(TicTacToeError::InviteExpired);
+            }
+
+            let value = match game.token {
+                Some(_) => {
+                    if msg::value() != 0 {
+                        return Err(TicTacToeError::InvalidStake);
+                    }
+
+                    // Check the game can be accepted before the transfer
+                    game.clone().accept(&player, game.stake, now)?;
+                    deposit_tokens(ttt, id, player).await?
+                }
+                None => msg::value(),
+            };
+
+            let game = ttt.get_game_mut(id)?;
+            game.accept(&player, value, now)?;
+
+            reply_and_notify(game, Event::Accepted(id));
+            schedule_timeout(id, game);
+        }
+        Action::Decline(id) => {
+            let game = ttt.get_game_mut(id)?;
+            game.decline(&msg::source())?;
+
+            reply_and_notify(game, Event::Declined(id));
+            ttt.on_game_ended(id).await;
+        }
+        Action::Join(id) => {
+            let player = msg::source();
+            let now = exec::block_height();
+            let game = ttt.get_game_mut(id)?;
+
+            let value = match game.token {
+                Some(_) => {
+                    if msg::value() != 0 {
+                        return Err(TicTacToeError::InvalidStake);
+                    }
+
+                    // Check the game can be joined before the transfer
+                    game.clone().join(&player, game.stake, now)?;
+                    deposit_tokens(ttt, id, player).await?
+                }
+                None => msg::value(),
+            };
+
+            let game = ttt.get_game_mut(id)?;
+            game.join(&player, value, now)?;
+
+            reply_and_notify(game, Event::Joined { id, player });
+            schedule_timeout(id, game);
+            ttt.add_player_game(player, id);
         }
         Action::Cancel(id) => {
-            let game = ttt.games.get_mut(&id).expect("Game not found!");
-            game.cancel(&msg::source());
+            let game = ttt.get_game_mut(id)?;
+            let player = msg::source();
+
+            if !game.cancel(&player)? {
+                reply_and_notify(game, Event::CancelOffered { id, player });
+                return Ok(());
+            }
+
+            reply_and_notify(game, Event::Canceled(id));
+            ttt.on_game_ended(id).await;
+        }
+        Action::Resign(id) => {
+            let game = ttt.get_game_mut(id)?;
+            game.resign(&msg::source())?;
 
-            msg::reply(Event::Canceled(id), 0).unwrap();
+            reply_and_notify(
+                game,
+                Event::Finished {
+                    id,
+                    winner: game.get_winner(),
+                },
+            );
+            ttt.on_game_ended(id).await;
         }
         Action::Turn { id, x, y } => {
-            let game = ttt.games.get_mut(&id).expect("Game not found!");
+            let game = ttt.get_game_mut(id)?;
             let player = msg::source();
 
             let is_game_finished = game.turn(
                 &player,
-                x.try_into().expect("TryInto overflow!"),
-                y.try_into().expect("TryInto overflow!"),
-            );
+                x.try_into().map_err(|_| TicTacToeError::OutOfBoard)?,
+                y.try_into().map_err(|_| TicTacToeError::OutOfBoard)?,
+                exec::block_height(),
+            )?;
             let maybe_winner = game.get_winner();
 
-            if is_game_finished {
-                msg::reply(
-                    Event::Finished {
-                        id,
-                        winner: maybe_winner,
-                    },
-                    0,
-                )
-                .unwrap();
-            } else {
-                msg::reply(Event::NewTurn { id, x, y, player }, 0).unwrap();
-            }
+            if is_game_finished {
+                reply_and_notify(
+                    game,
+                    Event::Finished {
+                        id,
+                        winner: maybe_winner,
+                    },
+                );
+            } else {
+                reply_and_notify(game, Event::NewTurn { id, x, y, player });
+
+                // Reply to `player` turn in the same message
+                if let Some(difficulty) = game.bot {
+                    let bot = exec::program_id();
+                    let seed = exec::block_timestamp() ^ id as u64;
+                    let (bot_x, bot_y) = game.get_bot_move(difficulty, seed);
+
+                    let is_game_finished = game
+                        .turn(&bot, bot_x, bot_y, exec::block_height())
+                        .expect("Invalid bot move!");
+
+                    let event = if is_game_finished {
+                        Event::Finished {
+                            id,
+                            winner: game.get_winner(),
+                        }
+                    } else {
+                        Event::NewTurn {
+                            id,
+                            x: bot_x as u64,
+                            y: bot_y as u64,
+                            player: bot,
+                        }
+                    };
+
+                    msg::send(player, &event, 0).expect("Unable to send bot turn!");
+                    notify(game, &event);
+                }
+            }
+
+            if game.is_ended() {
+                ttt.on_game_ended(id).await;
+            } else {
+                schedule_timeout(id, game);
+            }
+        }
+        Action::UltimateTurn { id, board, cell } => {
+            let game = ttt.get_game_mut(id)?;
+            let player = msg::source();
+
+            let is_game_finished = game.ultimate_turn(
+                &player,
+                board.try_into().map_err(|_| TicTacToeError::OutOfBoard)?,
+                cell.try_into().map_err(|_| TicTacToeError::OutOfBoard)?,
+                exec::block_height(),
+            )?;
+
+            let event = if is_game_finished {
+                Event::Finished {
+                    id,
+                    winner: game.get_winner(),
+                }
+            } else {
+                Event::NewUltimateTurn {
+                    id,
+                    board,
+                    cell,
+                    player,
+                }
+            };
+            reply_and_notify(game, event);
+
+            if game.is_ended() {
+                ttt.on_game_ended(id).await;
+            } else {
+                schedule_timeout(id, game);
+            }
+        }
+        Action::ClaimTimeout(id) => {
+            let game = ttt.get_game_mut(id)?;
+            let player = msg::source();
+            let now = exec::block_height();
+
+            if game.coin_flip.is_some() {
+                if player == exec::program_id() {
+                    if !game.expire_flip(now) {
+                        return Ok(());
+                    }
+                } else {
+                    game.claim_flip_timeout(&player, now)?;
+                }
+
+                let (player, _) = game.next_turn;
+                reply_and_notify(game, Event::FirstTurnDecided { id, player });
+                schedule_timeout(id, game);
+
+                return Ok(());
+            }
+
+            // Scheduled by the program itself, so the move could be made in time
+            if player == exec::program_id() {
+                if !game.expire(now) {
+                    return Ok(());
+                }
+            } else {
+                game.claim_timeout(&player, now)?;
+            }
+
+            reply_and_notify(
+                game,
+                Event::Finished {
+                    id,
+                    winner: game.get_winner(),
+                },
+            );
+            ttt.on_game_ended(id).await;
+        }
+        Action::Watch(id) => {
+            let game = ttt.get_game_mut(id)?;
+            let watcher = msg::source();
+
+            game.watch(&watcher)?;
+
+            msg::reply(Event::Watched { id, watcher }, 0).unwrap();
+        }
+        Action::Unwatch(id) => {
+            let game = ttt.get_game_mut(id)?;
+            let watcher = msg::source();
+
+            game.unwatch(&watcher)?;
+
+            msg::reply(Event::Unwatched { id, watcher }, 0).unwrap();
+        }
+        Action::KickWatcher { id, watcher } => {
+            let game = ttt.get_game_mut(id)?;
+            game.kick_watcher(&msg::source(), &watcher)?;
+
+            msg::reply(Event::WatcherKicked { id, watcher }, 0).unwrap();
+        }
+        Action::OfferDraw(id) => {
+            let game = ttt.get_game_mut(id)?;
+            let player = msg::source();
+
+            game.offer_draw(&player)?;
+
+            reply_and_notify(game, Event::DrawOffered { id, player });
+        }
+        Action::AcceptDraw(id) => {
+            let game = ttt.get_game_mut(id)?;
+            game.accept_draw(&msg::source())?;
+
+            reply_and_notify(game, Event::Finished { id, winner: None });
+            ttt.on_game_ended(id).await;
+        }
+        Action::DeclineDraw(id) => {
+            let game = ttt.get_game_mut(id)?;
+            game.decline_draw(&msg::source())?;
+
+            reply_and_notify(game, Event::DrawDeclined(id));
+        }
+        Action::RequestTakeback(id) => {
+            let game = ttt.get_game_mut(id)?;
+            let player = msg::source();
+
+            game.request_takeback(&player)?;
+
+            reply_and_notify(game, Event::TakebackRequested { id, player });
+        }
+        Action::ApproveTakeback(id) => {
+            let game = ttt.get_game_mut(id)?;
+            let Move { player, x, y, .. } =
+                game.approve_takeback(&msg::source(), exec::block_height())?;
+
+            reply_and_notify(game, Event::TakebackApplied { id, x, y, player });
+            schedule_timeout(id, game);
+        }
+        Action::CommitFlip { id, commitment } => {
+            let game = ttt.get_game_mut(id)?;
+            let player = msg::source();
+
+            game.commit_flip(&player, commitment, exec::block_height())?;
+
+            reply_and_notify(game, Event::FlipCommitted { id, player });
+            schedule_timeout(id, game);
+        }
+        Action::RevealFlip { id, secret } => {
+            let game = ttt.get_game_mut(id)?;
+            let player = msg::source();
+
+            let event = if game.reveal_flip(&player, secret, exec::block_height())? {
+                let (player, _) = game.next_turn;
+                Event::FirstTurnDecided { id, player }
+            } else {
+                Event::FlipRevealed { id, player }
+            };
+
+            reply_and_notify(game, event);
+            schedule_timeout(id, game);
+        }
+        Action::Prune { limit } => {
+            ttt.assert_owner(&msg::source())?;
+
+            let now = exec::block_height();
+            let expired = ttt.expire_invites(now, limit).await;
+            let count = ttt.prune(now, limit);
+            msg::reply(Event::Pruned { expired, count }, 0).unwrap();
+        }
+        Action::UpdateConfig(config) => {
+            ttt.assert_owner(&msg::source())?;
+            config.validate()?;
+
+            ttt.config = config.clone();
+            msg::reply(Event::ConfigUpdated(config), 0).unwrap();
+        }
+        Action::WithdrawFees => {
+            let treasury = msg::source();
+            if treasury != ttt.config.treasury {
+                return Err(TicTacToeError::NotTreasury);
+            }
+
+            let amount = ttt.fees_balance;
+            ttt.fees_balance = 0;
+
+            msg::reply(
+                Event::FeesWithdrawn {
+                    treasury,
+                    token: None,
+                    amount,
+                },
+                amount,
+            )
+            .unwrap();
+        }
+        Action::WithdrawTokenFees(token) => {
+            let treasury = msg::source();
+            if treasury != ttt.config.treasury {
+                return Err(TicTacToeError::NotTreasury);
+            }
+
+            let amount = ttt.token_fees_balance.remove(&token).unwrap_or_default();
+
+            if let Err(error) = transfer_tokens(token, exec::program_id(), treasury, amount).await {
+                // Kept for another withdrawal
+                let balance = ttt.token_fees_balance.entry(token).or_default();
+                *balance = balance.saturating_add(amount);
+
+                return Err(error);
+            }
+
+            msg::reply(
+                Event::FeesWithdrawn {
+                    treasury,
+                    token: Some(token),
+                    amount,
+                },
+                0,
+            )
+            .unwrap();
+        }
+        Action::ClaimPayout(token) => {
+            let player = msg::source();
+            let amount = ttt
+                .unpaid_payouts
+                .remove(&(player, token))
+                .ok_or(TicTacToeError::NoUnpaidPayout)?;
+
+            if let Err(error) = transfer_tokens(token, exec::program_id(), player, amount).await {
+                // Kept for another claim
+                let unpaid = ttt.unpaid_payouts.entry((player, token)).or_default();
+                *unpaid = unpaid.saturating_add(amount);
+
+                return Err(error);
+            }
+
+            msg::reply(
+                Event::PayoutClaimed {
+                    to: player,
+                    token,
+                    amount,
+                },
+                0,
+            )
+            .unwrap();
+        }
+        Action::Pause => {
+            ttt.assert_owner(&msg::source())?;
+
+            ttt.paused = true;
+            msg::reply(Event::Paused, 0).unwrap();
+        }
+        Action::Unpause => {
+            ttt.assert_owner(&msg::source())?;
+
+            ttt.paused = false;
+            msg::reply(Event::Unpaused, 0).unwrap();
+        }
+        Action::CreateTournament {
+            mut rules,
+            max_players,
+        } => {
+            ttt.assert_not_paused()?;
+
+            if !ttt.config.tournaments_enabled {
+                return Err(TicTacToeError::FeatureDisabled);
+            }
+
+            if rules.move_timeout == 0 {
+                rules.move_timeout = ttt.config.default_move_timeout;
+            }
+
+            let tournament = Tournament::init(msg::source(), rules, max_players)?;
+
+            ttt.tournament_nonce = ttt.tournament_nonce.checked_add(1).expect("Math overflow!");
+            let id = ttt.tournament_nonce;
+            ttt.tournaments.insert(id, tournament);
+
+            msg::reply(Event::TournamentCreated(id), 0).unwrap();
+        }
+        Action::RegisterForTournament(id) => {
+            let tournament = ttt.get_tournament_mut(id)?;
+            let player = msg::source();
+
+            tournament.register(&player)?;
+
+            msg::reply(Event::RegisteredForTournament { id, player }, 0).unwrap();
+        }
+        Action::StartTournament(id) => {
+            let tournament = ttt.get_tournament_mut(id)?;
+            let pairings = tournament.start(&msg::source())?;
+
+            msg::reply(Event::TournamentStarted(id), 0).unwrap();
+            ttt.open_tournament_games(id, pairings);
+        }
+    }
+
+    Ok(())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn meta_state() -> *mut [i32; 2] {
+    let query: StateQuery = msg::load().expect("Invalid StateQuery data!");
+    let ttt: &mut TicTacToe = TIC_TAC_TOE.get_or_insert(TicTacToe::default());
+
+    let encoded = match query {
+        StateQuery::GetNonce => StateQueryReply::Nonce(ttt.nonce),
+        StateQuery::GetFees => StateQueryReply::Fees {
+            collected: ttt.fees_collected,
+            balance: ttt.fees_balance,
+        },
+        StateQuery::GetTokenFees(token) => StateQueryReply::Fees {
+            collected: ttt
+                .token_fees_collected
+                .get(&token)
+                .copied()
+                .unwrap_or_default(),
+            balance: ttt
+                .token_fees_balance
+                .get(&token)
+                .copied()
+                .unwrap_or_default(),
+        },
+        StateQuery::GetUnpaidPayouts(player) => {
+            StateQueryReply::UnpaidPayouts(ttt.get_unpaid_payouts(&player))
+        }
+        StateQuery::GetConfig => StateQueryReply::Config {
+            owner: ttt.owner,
+            config: ttt.config.clone(),
+            paused: ttt.paused,
+        },
+        StateQuery::GetGamesLen => StateQueryReply::GamesLen(ttt.games.len() as GameID),
+        StateQuery::IsEnded(id) => {
+            let is_ended = ttt.archived_games.contains_key(&id)
+                || ttt.games.get(&id).expect("Game not found!").is_ended();
+
+            StateQueryReply::IsEnded(is_ended)
+        }
+        StateQuery::IsBoardFilled(id) => {
+            let game = ttt.games.get_mut(&id).expect("Game not found!");
+            StateQueryReply::IsBoardFilled(game.is_board_filled())
+        }
+        StateQuery::GetBoardMark((id, player)) => {
+            let game = ttt.games.get_mut(&id).expect("Game not found!");
+            StateQueryReply::BoardMark(game.get_board_mark(&player))
+        }
+        StateQuery::GetPlayer((id, board_mark)) => {
+            let game = ttt.games.get_mut(&id).expect("Game not found!");
+            StateQueryReply::Player(game.get_player(board_mark))
+        }
+        StateQuery::GetNextTurn(id) => {
+            let game = ttt.games.get_mut(&id).expect("Game not found!");
+            let (player, board_mark) = game.get_next_turn();
+            StateQueryReply::NextTurn { player, board_mark }
+        }
+        StateQuery::GetWinner(id) => {
+            let winner = match ttt.archived_games.get(&id) {
+                Some(archived_game) => archived_game.get_winner(),
+                None => ttt.games.get(&id).expect("Game not found!").get_winner(),
+            };
+
+            StateQueryReply::Winner(winner)
+        }
+        StateQuery::GetInvites(player) => {
+            let now = exec::block_height();
+            let invites = ttt
+                .games
+                .iter()
+                .filter(|(_, game)| {
+                    game.is_pending()
+                        && game.player_1 == Some(player)
+                        && !game.is_invite_expired(now)
+                })
+                .map(|(id, _)| *id)
+                .collect();
+
+            StateQueryReply::Invites(invites)
+        }
+        StateQuery::GetOpenGames => {
+            let open_games = ttt
+                .games
+                .iter()
+                .filter(|(_, game)| game.is_open())
+                .map(|(id, _)| *id)
+                .collect();
+
+            StateQueryReply::OpenGames(open_games)
+        }
+        StateQuery::GetMoves(id) => {
+            let game = ttt.games.get_mut(&id).expect("Game not found!");
+            StateQueryReply::Moves(game.moves.clone())
+        }
+        StateQuery::GetGamesByPlayer {
+            player,
+            status_filter,
+            offset,
+            limit,
+        } => StateQueryReply::Games(ttt.get_player_games(&player, status_filter, offset, limit)),
+        StateQuery::GetRating(player) => StateQueryReply::Rating(ttt.get_rating(&player)),
+        StateQuery::GetLeaderboard { by, offset, limit } => {
+            StateQueryReply::Leaderboard(ttt.leaderboard.get_page(by, offset, limit))
+        }
+        StateQuery::GetGame(id) => {
+            let game = ttt.games.get_mut(&id).expect("Game not found!");
+            StateQueryReply::Game(Box::new(game.get_view()))
+        }
+        StateQuery::GetArchivedGame(id) => {
+            let archived_game = ttt.archived_games.get(&id).expect("Game not found!");
+            StateQueryReply::ArchivedGame(archived_game.clone())
+        }
+        StateQuery::GetTournament(id) => {
+            let tournament = ttt.tournaments.get(&id).expect("Tournament not found!");
+            StateQueryReply::Tournament(tournament.clone())
+        }
+        StateQuery::GetBracket(id) => {
+            let tournament = ttt.tournaments.get(&id).expect("Tournament not found!");
+            StateQueryReply::Bracket(tournament.rounds.clone())
+        }
+    }
+    .encode();
+
+    gstd::util::to_leak_ptr(encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use bot::Difficulty;
+    use gtest::{Program, System, WasmProgram};
+    use std::{cell::RefCell, rc::Rc};
+
+    #[derive(Debug, Default)]
+    struct TokenState {
+        balances: BTreeMap<ActorId, u128>,
+        /// Transfers to these accounts are rejected.
+        rejected: BTreeSet<ActorId>,
+        /// Taken from each transfer, like some tokens do.
+        transfer_fee: u128,
+    }
+
+    /// Fungible token program, whose state is shared with the test.
+    #[derive(Debug)]
+    struct MockToken(Rc<RefCell<TokenState>>);
+
+    impl WasmProgram for MockToken {
+        fn init(&mut self, _payload: Vec<u8>) -> Result<Option<Vec<u8>>, &'static str> {
+            Ok(None)
+        }
+
+        fn handle(&mut self, payload: Vec<u8>) -> Result<Option<Vec<u8>>, &'static str> {
+            let (from, to, amount) = match FTAction::decode(&mut &payload[..]) {
+                Ok(FTAction::Transfer { from, to, amount }) => (from, to, amount),
+                _ => return Err("Unsupported action"),
+            };
+
+            let mut state = self.0.borrow_mut();
+            if state.rejected.contains(&to) {
+                return Err("Transfer rejected");
+            }
+
+            let balance = state.balances.entry(from).or_default();
+            *balance = balance.checked_sub(amount).ok_or("Not enough balance")?;
+
+            let amount = amount - state.transfer_fee.min(amount);
+            *state.balances.entry(to).or_default() += amount;
+
+            Ok(Some(FTEvent::Transfer { from, to, amount }.encode()))
+        }
+
+        fn handle_reply(&mut self, _payload: Vec<u8>) -> Result<(), &'static str> {
+            Ok(())
+        }
+
+        fn state(&mut self) -> Result<Vec<u8>, &'static str> {
+            Ok(Vec::new())
+        }
+    }
+
+    /// Creates token program with `balances` of test actors.
+    fn mock_token(sys: &System, id: u64, balances: &[(u64, u128)]) -> Rc<RefCell<TokenState>> {
+        let state = Rc::new(RefCell::new(TokenState {
+            balances: balances
+                .iter()
+                .map(|&(actor, balance)| (actor.into(), balance))
+                .collect(),
+            ..Default::default()
+        }));
+
+        Program::mock_with_id(sys, id, MockToken(state.clone()));
+        state
+    }
+
+    fn token_balance(state: &Rc<RefCell<TokenState>>, actor: u64) -> u128 {
+        state
+            .borrow()
+            .balances
+            .get(&actor.into())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn success_create_action() {
+        let sys = System::new();
+        sys.init_logger();
+
+        let owner: u64 = 3;
+        let player_0: u64 = 4;
+        let player_1: u64 = 5;
+
+        let tic_tac_toe = Program::current(&sys);
+        let result = tic_tac_toe.send(
+            owner,
+            InitConfig {
+                owner: owner.into(),
+                config: Default::default(),
+            },
+        );
+        assert!(result.log().is_empty());
+
+        let result = tic_tac_toe.send(
+            player_0,
+            Action::Create {
+                opponent: Some(player_1.into()),
+                rules: Default::default(),
+                bot: None,
+                token: None,
+            },
+        );
+        assert!(result.contains(&(
+            player_0,
+            Event::Created {
+                id: 1,
+                player_0: player_0.into(),
+                player_1: Some(player_1.into())
+            }
+            .encode()
+        )));
+    }
+
+    #[test]
+    fn success_cancel_action() {
+        let sys = System::new();
+        sys.init_logger();
+
+        let owner: u64 = 3;
+        let player_0: u64 = 4;
+        let player_1: u64 = 5;
+
+        let tic_tac_toe = Program::current(&sys);
+        let result = tic_tac_toe.send(
+            owner,
+            InitConfig {
+                owner: owner.into(),
+                config: Default::default(),
+            },
+        );
+        assert!(result.log().is_empty());
+
+        let result = tic_tac_toe.send(
+            player_0,
+            Action::Create {
+                opponent: Some(player_1.into()),
+                rules: Default::default(),
+                bot: None,
+                token: None,
+            },
+        );
+        assert!(result.contains(&(
+            player_0,
+            Event::Created {
+                id: 1,
+                player_0: player_0.into(),
+                player_1: Some(player_1.into())
+            }
+            .encode()
+        )));
+
+        let result = tic_tac_toe.send(player_1, Action::Cancel(1));
+        assert!(result.contains(&(player_1, Event::Canceled(1).encode())));
+    }
+
+    #[test]
+    fn success_resign_action() {
+        let sys = System::new();
+        sys.init_logger();
+
+        let owner: u64 = 3;
+        let player_0: u64 = 4;
+        let player_1: u64 = 5;
+
+        let tic_tac_toe = Program::current(&sys);
+        let result = tic_tac_toe.send(
+            owner,
+            InitConfig {
+                owner: owner.into(),
+                config: Default::default(),
+            },
+        );
+        assert!(result.log().is_empty());
+
+        tic_tac_toe.send(
+            player_0,
+            Action::Create {
+                opponent: Some(player_1.into()),
+                rules: Default::default(),
+                bot: None,
+                token: None,
+            },
+        );
+        tic_tac_toe.send(player_1, Action::Accept(1));
+        tic_tac_toe.send(player_0, Action::Turn { id: 1, x: 0, y: 0 });
+
+        let result = tic_tac_toe.send(player_1, Action::Cancel(1));
+        assert!(result.contains(&(
+            player_1,
+            Event::CancelOffered {
+                id: 1,
+                player: player_1.into()
+            }
+            .encode()
+        )));
+
+        let result = tic_tac_toe.send(player_1, Action::Resign(1));
+        assert!(result.contains(&(
+            player_1,
+            Event::Finished {
+                id: 1,
+                winner: Some(player_0.into())
+            }
+            .encode()
+        )));
+    }
+
+    #[test]
+    fn success_config_actions() {
+        let sys = System::new();
+        sys.init_logger();
+
+        let owner: u64 = 3;
+        let player_0: u64 = 4;
+        let create = Action::Create {
+            opponent: None,
+            rules: Default::default(),
+            bot: None,
+            token: None,
+        };
+
+        let tic_tac_toe = Program::current(&sys);
+        let result = tic_tac_toe.send(
+            owner,
+            InitConfig {
+                owner: owner.into(),
+                config: Default::default(),
+            },
+        );
+        assert!(result.log().is_empty());
+
+        let result = tic_tac_toe.send(player_0, Action::Pause);
+        assert!(result.contains(&(player_0, Event::Error(TicTacToeError::NotOwner).encode())));
+
+        let result = tic_tac_toe.send(owner, Action::Pause);
+        assert!(result.contains(&(owner, Event::Paused.encode())));
+
+        let result = tic_tac_toe.send(player_0, create.clone());
+        assert!(result.contains(&(player_0, Event::Error(TicTacToeError::Paused).encode())));
+
+        let result = tic_tac_toe.send(owner, Action::Unpause);
+        assert!(result.contains(&(owner, Event::Unpaused.encode())));
+
+        let config = Config {
+            max_open_games: 1,
+            ..Default::default()
+        };
+        let result = tic_tac_toe.send(owner, Action::UpdateConfig(config.clone()));
+        assert!(result.contains(&(owner, Event::ConfigUpdated(config).encode())));
+
+        let result = tic_tac_toe.send(player_0, create.clone());
+        assert!(!result.main_failed());
+
+        let result = tic_tac_toe.send(player_0, create);
+        assert!(result.contains(&(
+            player_0,
+            Event::Error(TicTacToeError::TooManyOpenGames).encode()
+        )));
+    }
+
+    #[test]
+    fn success_fee_actions() {
+        let sys = System::new();
+        sys.init_logger();
+
+        let owner: u64 = 3;
+        let player_0: u64 = 4;
+        let player_1: u64 = 5;
+        let treasury: u64 = 6;
+        let stake = 1_000;
+        let game_id = 1;
+
+        for player in [player_0, player_1] {
+            sys.mint_to(player, stake);
+        }
+
+        let tic_tac_toe = Program::current(&sys);
+        let result = tic_tac_toe.send(
+            owner,
+            InitConfig {
+                owner: owner.into(),
+                config: Config {
+                    fee_bps: 250,
+                    treasury: treasury.into(),
+                    ..Default::default()
+                },
+            },
+        );
+        assert!(result.log().is_empty());
+
+        tic_tac_toe.send_with_value(
+            player_0,
+            Action::Create {
+                opponent: Some(player_1.into()),
+                rules: Default::default(),
+                bot: None,
+                token: None,
+            },
+            stake,
+        );
+        tic_tac_toe.send_with_value(player_1, Action::Accept(game_id), stake);
+
+        // Fee of the started game doesn't change
+        tic_tac_toe.send(
+            owner,
+            Action::UpdateConfig(Config {
+                fee_bps: config::MAX_FEE_BPS,
+                treasury: treasury.into(),
+                ..Default::default()
+            }),
+        );
+
+        let result = tic_tac_toe.send(player_1, Action::Resign(game_id));
+        assert!(result.contains(&(
+            player_0,
+            Event::Payout {
+                id: game_id,
+                to: player_0.into(),
+                token: None,
+                gross: 2_000,
+                fee: 50,
+                net: 1_950,
+            }
+            .encode()
+        )));
+
+        let result = tic_tac_toe.send(player_0, Action::WithdrawFees);
+        assert!(result.contains(&(player_0, Event::Error(TicTacToeError::NotTreasury).encode())));
+
+        for amount in [50, 0] {
+            let result = tic_tac_toe.send(treasury, Action::WithdrawFees);
+            assert!(result.contains(&(
+                treasury,
+                Event::FeesWithdrawn {
+                    treasury: treasury.into(),
+                    token: None,
+                    amount,
+                }
+                .encode()
+            )));
+        }
+    }
+
+    #[test]
+    fn success_token_deposit_actions() {
+        let sys = System::new();
+        sys.init_logger();
+
+        let owner: u64 = 3;
+        let player_0: u64 = 4;
+        let player_1: u64 = 5;
+        let player_2: u64 = 6;
+        let token: u64 = 10;
+
+        let token_state = mock_token(&sys, token, &[(player_0, 1_500)]);
+        let tic_tac_toe = Program::current(&sys);
+        let result = tic_tac_toe.send(
+            owner,
+            InitConfig {
+                owner: owner.into(),
+                config: Default::default(),
+            },
+        );
+        assert!(result.log().is_empty());
+
+        let create = |opponent: Option<u64>, amount| Action::Create {
+            opponent: opponent.map(Into::into),
+            rules: Default::default(),
+            bot: None,
+            token: Some(TokenStake {
+                program: token.into(),
+                amount,
+            }),
+        };
+
+        // Game isn't created without the stake
+        let result = tic_tac_toe.send(player_0, create(Some(player_1), 2_000));
+        assert!(result.contains(&(
+            player_0,
+            Event::Error(TicTacToeError::TokenTransferFailed).encode()
+        )));
+        assert_eq!(token_balance(&token_state, player_0), 1_500);
+
+        let result = tic_tac_toe.send(player_0, create(Some(player_1), 1_000));
+        assert!(result.contains(&(
+            player_0,
+            Event::Created {
+                id: 1,
+                player_0: player_0.into(),
+                player_1: Some(player_1.into()),
+            }
+            .encode()
+        )));
+        assert_eq!(token_balance(&token_state, player_0), 500);
+
+        // Rejected deposit leaves the invite pending
+        let result = tic_tac_toe.send(player_1, Action::Accept(1));
+        assert!(result.contains(&(
+            player_1,
+            Event::Error(TicTacToeError::TokenTransferFailed).encode()
+        )));
+
+        token_state
+            .borrow_mut()
+            .balances
+            .insert(player_1.into(), 1_000);
+        let result = tic_tac_toe.send(player_1, Action::Accept(1));
+        assert!(result.contains(&(player_1, Event::Accepted(1).encode())));
+        assert_eq!(token_balance(&token_state, player_1), 0);
+
+        tic_tac_toe.send(player_0, create(None, 500));
+
+        // Rejected deposit leaves the game open
+        let result = tic_tac_toe.send(player_2, Action::Join(2));
+        assert!(result.contains(&(
+            player_2,
+            Event::Error(TicTacToeError::TokenTransferFailed).encode()
+        )));
+
+        token_state
+            .borrow_mut()
+            .balances
+            .insert(player_2.into(), 500);
+        let result = tic_tac_toe.send(player_2, Action::Join(2));
+        assert!(result.contains(&(
+            player_2,
+            Event::Joined {
+                id: 2,
+                player: player_2.into(),
+            }
+            .encode()
+        )));
+        assert_eq!(token_balance(&token_state, player_2), 0);
+    }
+
+    #[test]
+    fn error_token_transfer_amount() {
+        let sys = System::new();
+        sys.init_logger();
+
+        let owner: u64 = 3;
+        let player_0: u64 = 4;
+        let player_1: u64 = 5;
+        let token: u64 = 10;
+
+        let token_state = mock_token(&sys, token, &[(player_0, 1_000)]);
+        token_state.borrow_mut().transfer_fee = 1;
+
+        let tic_tac_toe = Program::current(&sys);
+        let result = tic_tac_toe.send(
+            owner,
+            InitConfig {
+                owner: owner.into(),
+                config: Default::default(),
+            },
+        );
+        assert!(result.log().is_empty());
+
+        // Game isn't started with less than the stake
+        let result = tic_tac_toe.send(
+            player_0,
+            Action::Create {
+                opponent: Some(player_1.into()),
+                rules: Default::default(),
+                bot: None,
+                token: Some(TokenStake {
+                    program: token.into(),
+                    amount: 1_000,
+                }),
+            },
+        );
+        assert!(result.contains(&(
+            player_0,
+            Event::Error(TicTacToeError::TokenTransferFailed).encode()
+        )));
+
+        let result = tic_tac_toe.send(player_1, Action::Accept(1));
+        assert!(result.contains(&(
+            player_1,
+            Event::Error(TicTacToeError::GameNotFound).encode()
+        )));
+    }
+
+    #[test]
+    fn success_token_payout_actions() {
+        let sys = System::new();
+        sys.init_logger();
+
+        let owner: u64 = 3;
+        let player_0: u64 = 4;
+        let player_1: u64 = 5;
+        let treasury: u64 = 6;
+        let token: u64 = 10;
+        let stake = 1_000;
+        let game_id = 1;
+
+        let token_state = mock_token(&sys, token, &[(player_0, stake), (player_1, stake)]);
+        let tic_tac_toe = Program::current(&sys);
+        let result = tic_tac_toe.send(
+            owner,
+            InitConfig {
+                owner: owner.into(),
+                config: Config {
+                    fee_bps: 250,
+                    treasury: treasury.into(),
+                    ..Default::default()
+                },
+            },
+        );
+        assert!(result.log().is_empty());
+
+        tic_tac_toe.send(
+            player_0,
+            Action::Create {
+                opponent: Some(player_1.into()),
+                rules: Default::default(),
+                bot: None,
+                token: Some(TokenStake {
+                    program: token.into(),
+                    amount: stake,
+                }),
+            },
+        );
+        tic_tac_toe.send(player_1, Action::Accept(game_id));
+
+        // Rejected payout is kept for the winner
+        token_state.borrow_mut().rejected.insert(player_0.into());
+        let result = tic_tac_toe.send(player_1, Action::Resign(game_id));
+        assert!(result.contains(&(
+            player_0,
+            Event::PayoutFailed {
+                id: game_id,
+                to: player_0.into(),
+                token: token.into(),
+                amount: 1_950,
+            }
+            .encode()
+        )));
+
+        let result = tic_tac_toe.send(player_0, Action::ClaimPayout(token.into()));
+        assert!(result.contains(&(
+            player_0,
+            Event::Error(TicTacToeError::TokenTransferFailed).encode()
+        )));
+
+        token_state.borrow_mut().rejected.clear();
+        let result = tic_tac_toe.send(player_0, Action::ClaimPayout(token.into()));
+        assert!(result.contains(&(
+            player_0,
+            Event::PayoutClaimed {
+                to: player_0.into(),
+                token: token.into(),
+                amount: 1_950,
+            }
+            .encode()
+        )));
+        assert_eq!(token_balance(&token_state, player_0), 1_950);
+
+        let result = tic_tac_toe.send(player_0, Action::ClaimPayout(token.into()));
+        assert!(result.contains(&(
+            player_0,
+            Event::Error(TicTacToeError::NoUnpaidPayout).encode()
+        )));
+
+        // Fee is kept in the token
+        let result = tic_tac_toe.send(player_0, Action::WithdrawTokenFees(token.into()));
+        assert!(result.contains(&(player_0, Event::Error(TicTacToeError::NotTreasury).encode())));
+
+        for amount in [50, 0] {
+            let result = tic_tac_toe.send(treasury, Action::WithdrawTokenFees(token.into()));
+            assert!(result.contains(&(
+                treasury,
+                Event::FeesWithdrawn {
+                    treasury: treasury.into(),
+                    token: Some(token.into()),
+                    amount,
+                }
+                .encode()
+            )));
+        }
+        assert_eq!(token_balance(&token_state, treasury), 50);
+    }
+
+    #[test]
+    fn success_decline_action() {
+        let sys = System::new();
+        sys.init_logger();
+
+        let owner: u64 = 3;
+        let player_0: u64 = 4;
+        let player_1: u64 = 5;
+
+        let tic_tac_toe = Program::current(&sys);
+        let result = tic_tac_toe.send(
+            owner,
+            InitConfig {
+                owner: owner.into(),
+                config: Default::default(),
+            },
+        );
+        assert!(result.log().is_empty());
+
+        tic_tac_toe.send(
+            player_0,
+            Action::Create {
+                opponent: Some(player_1.into()),
+                rules: Default::default(),
+                bot: None,
+                token: None,
+            },
+        );
+
+        let result = tic_tac_toe.send(player_0, Action::Accept(1));
+        assert!(result.contains(&(
+            player_0,
+            Event::Error(TicTacToeError::InviteNotAddressedToYou).encode()
+        )));
+
+        let result = tic_tac_toe.send(player_1, Action::Decline(1));
+        assert!(result.contains(&(player_1, Event::Declined(1).encode())));
+
+        let result = tic_tac_toe.send(player_1, Action::Accept(1));
+        assert!(result.contains(&(
+            player_1,
+            Event::Error(TicTacToeError::GameNotPending).encode()
+        )));
+    }
+
+    #[test]
+    fn success_expired_invite_action() {
+        let sys = System::new();
+        sys.init_logger();
+
+        let owner: u64 = 3;
+        let player_0: u64 = 4;
+        let player_1: u64 = 5;
+
+        let tic_tac_toe = Program::current(&sys);
+        let result = tic_tac_toe.send(
+            owner,
+            InitConfig {
+                owner: owner.into(),
+                config: Config {
+                    wagers_enabled: true,
+                    ..Default::default()
+                },
+            },
+        );
+        assert!(result.log().is_empty());
+
+        for _ in 0..2 {
+            sys.mint_to(player_0, 1000);
+            tic_tac_toe.send_with_value(
+                player_0,
+                Action::Create {
+                    opponent: Some(player_1.into()),
+                    rules: Default::default(),
+                    bot: None,
+                    token: None,
+                },
+                1000,
+            );
         }
-    }
-}
 
-#[no_mangle]
-pub unsafe extern "C" fn meta_state() -> *mut [i32; 2] {
-    let query: StateQuery = msg::load().expect("Invalid StateQuery data!");
-    let ttt: &mut TicTacToe = TIC_TAC_TOE.get_or_insert(TicTacToe::default());
+        sys.spend_blocks(INVITE_EXPIRATION_BLOCKS + 1);
 
-    let encoded = match query {
-        StateQuery::GetNonce => StateQueryReply::Nonce(ttt.nonce),
-        StateQuery::GetGamesLen => StateQueryReply::GamesLen(ttt.games.len() as GameID),
-        StateQuery::IsEnded(id) => {
-            let game = ttt.games.get_mut(&id).expect("Game not found!");
-            StateQueryReply::IsEnded(game.is_ended())
-        }
-        StateQuery::IsBoardFilled(id) => {
-            let game = ttt.games.get_mut(&id).expect("Game not found!");
-            StateQueryReply::IsBoardFilled(game.is_board_filled())
-        }
-        StateQuery::GetBoardMark((id, player)) => {
-            let game = ttt.games.get_mut(&id).expect("Game not found!");
-            StateQueryReply::BoardMark(game.get_board_mark(&player))
-        }
-        StateQuery::GetPlayer((id, board_mark)) => {
-            let game = ttt.games.get_mut(&id).expect("Game not found!");
-            StateQueryReply::Player(game.get_player(board_mark))
-        }
-        StateQuery::GetNextTurn(id) => {
-            let game = ttt.games.get_mut(&id).expect("Game not found!");
-            let (player, board_mark) = game.get_next_turn();
-            StateQueryReply::NextTurn { player, board_mark }
-        }
-        StateQuery::GetWinner(id) => {
-            let game = ttt.games.get_mut(&id).expect("Game not found!");
-            StateQueryReply::Winner(game.get_winner())
-        }
-    }
-    .encode();
+        // Expired invite is canceled on access and the stake is refunded
+        let result = tic_tac_toe.send(player_1, Action::Accept(1));
+        assert!(result.contains(&(
+            player_1,
+            Event::Error(TicTacToeError::InviteExpired).encode()
+        )));
+        assert!(result.contains(&(player_0, Event::Canceled(1).encode())));
+        assert_eq!(sys.balance_of(player_0), 1000);
 
-    gstd::util::to_leak_ptr(encoded)
-}
+        // Rest of expired invites are canceled by prune
+        let result = tic_tac_toe.send(owner, Action::Prune { limit: 10 });
+        assert!(result.contains(&(
+            owner,
+            Event::Pruned {
+                expired: 1,
+                count: 0
+            }
+            .encode()
+        )));
+        assert!(result.contains(&(player_0, Event::Canceled(2).encode())));
+        assert_eq!(sys.balance_of(player_0), 2000);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use gtest::{Program, System};
+        let result = tic_tac_toe.send(player_1, Action::Accept(2));
+        assert!(result.contains(&(
+            player_1,
+            Event::Error(TicTacToeError::GameNotPending).encode()
+        )));
+    }
 
     #[test]
-    fn success_create_action() {
+    fn success_prune_ended_invite() {
         let sys = System::new();
         sys.init_logger();
 
@@ -152,23 +1907,76 @@ mod tests {
         let player_1: u64 = 5;
 
         let tic_tac_toe = Program::current(&sys);
-        let result = tic_tac_toe.send_bytes(owner, [0u8; 1]);
+        let result = tic_tac_toe.send(
+            owner,
+            InitConfig {
+                owner: owner.into(),
+                config: Default::default(),
+            },
+        );
         assert!(result.log().is_empty());
 
-        let result = tic_tac_toe.send(player_0, Action::Create(player_1.into()));
+        for _ in 0..3 {
+            tic_tac_toe.send(
+                player_0,
+                Action::Create {
+                    opponent: Some(player_1.into()),
+                    rules: Default::default(),
+                    bot: None,
+                    token: None,
+                },
+            );
+        }
+
+        tic_tac_toe.send(player_1, Action::Accept(3));
+        tic_tac_toe.send(player_1, Action::Resign(3));
+
+        sys.spend_blocks(GAME_RETENTION_BLOCKS + 1);
+
+        // Invite 1 expires, while ended invite 3 is archived behind invite 2
+        let result = tic_tac_toe.send(owner, Action::Prune { limit: 1 });
+        assert!(result.contains(&(
+            owner,
+            Event::Pruned {
+                expired: 1,
+                count: 1
+            }
+            .encode()
+        )));
+
+        // Archived invite is skipped
+        let result = tic_tac_toe.send(owner, Action::Prune { limit: 10 });
+        assert!(result.contains(&(
+            owner,
+            Event::Pruned {
+                expired: 1,
+                count: 0
+            }
+            .encode()
+        )));
+
+        let result = tic_tac_toe.send(
+            player_0,
+            Action::Create {
+                opponent: Some(player_1.into()),
+                rules: Default::default(),
+                bot: None,
+                token: None,
+            },
+        );
         assert!(result.contains(&(
             player_0,
             Event::Created {
-                id: 1,
+                id: 4,
                 player_0: player_0.into(),
-                player_1: player_1.into()
+                player_1: Some(player_1.into()),
             }
             .encode()
         )));
     }
 
     #[test]
-    fn success_cancel_action() {
+    fn success_join_action() {
         let sys = System::new();
         sys.init_logger();
 
@@ -177,22 +1985,132 @@ mod tests {
         let player_1: u64 = 5;
 
         let tic_tac_toe = Program::current(&sys);
-        let result = tic_tac_toe.send_bytes(owner, [0u8; 1]);
+        let result = tic_tac_toe.send(
+            owner,
+            InitConfig {
+                owner: owner.into(),
+                config: Default::default(),
+            },
+        );
         assert!(result.log().is_empty());
 
-        let result = tic_tac_toe.send(player_0, Action::Create(player_1.into()));
+        let result = tic_tac_toe.send(
+            player_0,
+            Action::Create {
+                opponent: None,
+                rules: Default::default(),
+                bot: None,
+                token: None,
+            },
+        );
         assert!(result.contains(&(
             player_0,
             Event::Created {
                 id: 1,
                 player_0: player_0.into(),
-                player_1: player_1.into()
+                player_1: None
             }
             .encode()
         )));
 
-        let result = tic_tac_toe.send(player_1, Action::Cancel(1));
-        assert!(result.contains(&(player_1, Event::Canceled(1).encode())));
+        let result = tic_tac_toe.send(player_0, Action::Join(1));
+        assert!(result.contains(&(player_0, Event::Error(TicTacToeError::SamePlayers).encode())));
+
+        let result = tic_tac_toe.send(player_1, Action::Join(1));
+        assert!(result.contains(&(
+            player_1,
+            Event::Joined {
+                id: 1,
+                player: player_1.into()
+            }
+            .encode()
+        )));
+    }
+
+    #[test]
+    fn success_get_player_games() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let player_1 = ActorId::new([1u8; 32]);
+        let player_2 = ActorId::new([2u8; 32]);
+        let mut ttt = TicTacToe::default();
+
+        for (id, opponent) in [(1, player_1), (2, player_2), (3, player_1)] {
+            ttt.games.insert(
+                id,
+                Game::init(player_0, Some(opponent), Default::default(), 0, 0).unwrap(),
+            );
+            ttt.add_player_game(player_0, id);
+            ttt.add_player_game(opponent, id);
+        }
+
+        ttt.games.get_mut(&2).unwrap().decline(&player_2).unwrap();
+        ttt.games
+            .get_mut(&3)
+            .unwrap()
+            .accept(&player_1, 0, 0)
+            .unwrap();
+
+        assert_eq!(ttt.get_player_games(&player_0, None, 0, 10), vec![1, 2, 3]);
+        assert_eq!(ttt.get_player_games(&player_0, None, 1, 1), vec![2]);
+        assert_eq!(
+            ttt.get_player_games(&player_0, None, 1, u64::MAX),
+            vec![2, 3]
+        );
+        assert!(ttt
+            .get_player_games(&player_0, None, u64::MAX, 10)
+            .is_empty());
+        assert_eq!(ttt.get_player_games(&player_1, None, 0, 10), vec![1, 3]);
+        assert_eq!(
+            ttt.get_player_games(&player_0, Some(StatusFilter::Pending), 0, 10),
+            vec![1]
+        );
+        assert_eq!(
+            ttt.get_player_games(&player_0, Some(StatusFilter::Active), 0, 10),
+            vec![3]
+        );
+        assert_eq!(
+            ttt.get_player_games(&player_0, Some(StatusFilter::Canceled), 0, 10),
+            vec![2]
+        );
+        assert!(ttt
+            .get_player_games(&ActorId::new([3u8; 32]), None, 0, 10)
+            .is_empty());
+    }
+
+    #[test]
+    fn success_prune() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let player_1 = ActorId::new([1u8; 32]);
+        let mut ttt = TicTacToe::default();
+
+        for id in 1..=3 {
+            let mut game = Game::init(player_0, Some(player_1), Default::default(), 0, 0).unwrap();
+            game.decline(&player_1).unwrap();
+
+            ttt.games.insert(id, game);
+            ttt.add_player_game(player_0, id);
+            ttt.ended_games.push_back((id as u32 * 10, id));
+        }
+
+        assert_eq!(ttt.prune(10 + GAME_RETENTION_BLOCKS, 10), 0);
+        assert_eq!(ttt.prune(21 + GAME_RETENTION_BLOCKS, 1), 1);
+        assert_eq!(ttt.prune(21 + GAME_RETENTION_BLOCKS, 10), 1);
+
+        assert_eq!(ttt.games.keys().copied().collect::<Vec<_>>(), vec![3]);
+        assert_eq!(
+            ttt.archived_games.get(&1),
+            Some(&ArchivedGame {
+                player_0,
+                player_1: Some(player_1),
+                status: GameStatus::Canceled,
+                ended_at: 10,
+            })
+        );
+        assert_eq!(ttt.ended_games, VecDeque::from([(30, 3)]));
+        assert_eq!(
+            ttt.get_player_games(&player_0, Some(StatusFilter::Canceled), 0, 10),
+            vec![1, 2, 3]
+        );
     }
 
     #[test]
@@ -203,23 +2121,117 @@ mod tests {
         let owner: u64 = 3;
         let player_0: u64 = 4;
         let player_1: u64 = 5;
+        let watcher: u64 = 6;
         let game_id = 1;
 
         let tic_tac_toe = Program::current(&sys);
-        let result = tic_tac_toe.send_bytes(owner, [0u8; 1]);
+        let result = tic_tac_toe.send(
+            owner,
+            InitConfig {
+                owner: owner.into(),
+                config: Default::default(),
+            },
+        );
         assert!(result.log().is_empty());
 
-        let result = tic_tac_toe.send(player_0, Action::Create(player_1.into()));
+        let result = tic_tac_toe.send(
+            player_0,
+            Action::Create {
+                opponent: Some(player_1.into()),
+                rules: Default::default(),
+                bot: None,
+                token: None,
+            },
+        );
         assert!(result.contains(&(
             player_0,
             Event::Created {
                 id: game_id,
                 player_0: player_0.into(),
-                player_1: player_1.into()
+                player_1: Some(player_1.into())
+            }
+            .encode()
+        )));
+
+        let result = tic_tac_toe.send(player_1, Action::Accept(game_id));
+        assert!(result.contains(&(player_1, Event::Accepted(game_id).encode())));
+        assert!(result.contains(&(player_0, Event::Accepted(game_id).encode())));
+
+        let result = tic_tac_toe.send(watcher, Action::Watch(game_id));
+        assert!(result.contains(&(
+            watcher,
+            Event::Watched {
+                id: game_id,
+                watcher: watcher.into()
             }
             .encode()
         )));
 
+        sys.mint_to(player_0, 100);
+        let result = tic_tac_toe.send_with_value(
+            player_0,
+            Action::Turn {
+                id: game_id,
+                x: 0,
+                y: 0,
+            },
+            100,
+        );
+        assert!(result.contains(&(
+            player_0,
+            Event::Error(TicTacToeError::InvalidStake).encode()
+        )));
+
+        let result = tic_tac_toe.send(
+            player_0,
+            Action::Turn {
+                id: game_id,
+                x: 0,
+                y: 0,
+            },
+        );
+        let event = Event::NewTurn {
+            id: game_id,
+            x: 0,
+            y: 0,
+            player: player_0.into(),
+        }
+        .encode();
+        for player in [player_0, player_1, watcher] {
+            assert!(result.contains(&(player, event.clone())));
+        }
+    }
+
+    #[test]
+    fn success_bot_turn_action() {
+        let sys = System::new();
+        sys.init_logger();
+
+        let owner: u64 = 3;
+        let player_0: u64 = 4;
+        let game_id = 1;
+
+        let tic_tac_toe = Program::current(&sys);
+        let result = tic_tac_toe.send(
+            owner,
+            InitConfig {
+                owner: owner.into(),
+                config: Default::default(),
+            },
+        );
+        assert!(result.log().is_empty());
+
+        let result = tic_tac_toe.send(
+            player_0,
+            Action::Create {
+                opponent: None,
+                rules: Default::default(),
+                bot: Some(Difficulty::Hard),
+                token: None,
+            },
+        );
+        assert!(!result.main_failed());
+
         let result = tic_tac_toe.send(
             player_0,
             Action::Turn {
@@ -238,6 +2250,114 @@ mod tests {
             }
             .encode()
         )));
+        assert!(result.contains(&(
+            player_0,
+            Event::NewTurn {
+                id: game_id,
+                x: 1,
+                y: 1,
+                player: tic_tac_toe.id().into()
+            }
+            .encode()
+        )));
+    }
+
+    #[test]
+    fn success_tournament_action() {
+        let sys = System::new();
+        sys.init_logger();
+
+        let owner: u64 = 3;
+        let player_0: u64 = 4;
+        let player_1: u64 = 5;
+        let tournament_id = 1;
+        let game_id = 1;
+
+        let tic_tac_toe = Program::current(&sys);
+        let result = tic_tac_toe.send(
+            owner,
+            InitConfig {
+                owner: owner.into(),
+                config: Default::default(),
+            },
+        );
+        assert!(result.log().is_empty());
+
+        let result = tic_tac_toe.send(
+            owner,
+            Action::CreateTournament {
+                rules: Default::default(),
+                max_players: 4,
+            },
+        );
+        assert!(result.contains(&(owner, Event::TournamentCreated(tournament_id).encode())));
+
+        for player in [player_0, player_1] {
+            let result = tic_tac_toe.send(player, Action::RegisterForTournament(tournament_id));
+            assert!(result.contains(&(
+                player,
+                Event::RegisteredForTournament {
+                    id: tournament_id,
+                    player: player.into()
+                }
+                .encode()
+            )));
+        }
+
+        let result = tic_tac_toe.send(player_0, Action::StartTournament(tournament_id));
+        assert!(result.contains(&(
+            player_0,
+            Event::Error(TicTacToeError::NotTournamentCreator).encode()
+        )));
+
+        let result = tic_tac_toe.send(owner, Action::StartTournament(tournament_id));
+        assert!(result.contains(&(owner, Event::TournamentStarted(tournament_id).encode())));
+        for player in [player_0, player_1] {
+            assert!(result.contains(&(
+                player,
+                Event::Created {
+                    id: game_id,
+                    player_0: player_0.into(),
+                    player_1: Some(player_1.into())
+                }
+                .encode()
+            )));
+        }
+
+        let result = tic_tac_toe.send(player_0, Action::Cancel(game_id));
+        assert!(result.contains(&(
+            player_0,
+            Event::Error(TicTacToeError::TournamentGameCantBeCanceled).encode()
+        )));
+
+        for (player, x, y) in [
+            (player_0, 0, 0),
+            (player_1, 1, 1),
+            (player_0, 0, 1),
+            (player_1, 2, 2),
+        ] {
+            let result = tic_tac_toe.send(player, Action::Turn { id: game_id, x, y });
+            assert!(!result.main_failed());
+        }
+
+        let result = tic_tac_toe.send(
+            player_0,
+            Action::Turn {
+                id: game_id,
+                x: 0,
+                y: 2,
+            },
+        );
+        for player in [player_0, player_1] {
+            assert!(result.contains(&(
+                player,
+                Event::TournamentFinished {
+                    id: tournament_id,
+                    winner: player_0.into()
+                }
+                .encode()
+            )));
+        }
     }
 
     #[test]
@@ -251,20 +2371,37 @@ mod tests {
         let game_id = 1;
 
         let tic_tac_toe = Program::current(&sys);
-        let result = tic_tac_toe.send_bytes(owner, [0u8; 1]);
+        let result = tic_tac_toe.send(
+            owner,
+            InitConfig {
+                owner: owner.into(),
+                config: Default::default(),
+            },
+        );
         assert!(result.log().is_empty());
 
-        let result = tic_tac_toe.send(player_0, Action::Create(player_1.into()));
+        let result = tic_tac_toe.send(
+            player_0,
+            Action::Create {
+                opponent: Some(player_1.into()),
+                rules: Default::default(),
+                bot: None,
+                token: None,
+            },
+        );
         assert!(result.contains(&(
             player_0,
             Event::Created {
                 id: game_id,
                 player_0: player_0.into(),
-                player_1: player_1.into()
+                player_1: Some(player_1.into())
             }
             .encode()
         )));
 
+        let result = tic_tac_toe.send(player_1, Action::Accept(game_id));
+        assert!(result.contains(&(player_1, Event::Accepted(game_id).encode())));
+
         let result = tic_tac_toe.send(
             player_0,
             Action::Turn {
@@ -357,5 +2494,23 @@ mod tests {
             }
             .encode()
         )));
+        assert!(result.contains(&(
+            player_0,
+            Event::RatingChanged {
+                player: player_0.into(),
+                old_rating: 1200,
+                new_rating: 1216
+            }
+            .encode()
+        )));
+        assert!(result.contains(&(
+            player_1,
+            Event::RatingChanged {
+                player: player_1.into(),
+                old_rating: 1200,
+                new_rating: 1184
+            }
+            .encode()
+        )));
     }
 }
@@ -0,0 +1,242 @@
+use codec::{Decode, Encode};
+use gstd::{prelude::*, ActorId};
+
+/// Rating of a player without rated games.
+pub const INITIAL_RATING: u32 = 1200;
+/// Maximum rating change per game.
+pub const K_FACTOR: i64 = 32;
+
+pub const WIN_SCORE: u32 = 1000;
+pub const DRAW_SCORE: u32 = 500;
+pub const LOSS_SCORE: u32 = 0;
+
+const RATING_DIFF_STEP: u32 = 25;
+
+/// Expected score in thousandths for each `RATING_DIFF_STEP`
+/// of rating advantage, from 0 up to 800 points.
+const EXPECTED_SCORES: [u32; 33] = [
+    500, 536, 571, 606, 640, 673, 703, 733, 760, 785, 808, 830, 849, 867, 882, 896, 909, 920, 930,
+    939, 947, 954, 960, 965, 969, 973, 977, 980, 983, 985, 987, 989, 990,
+];
+
+/// Returns expected score in thousandths of a player
+/// with `rating` against a player with `opponent_rating`.
+pub fn expected_score(rating: u32, opponent_rating: u32) -> u32 {
+    if rating < opponent_rating {
+        return WIN_SCORE - expected_score(opponent_rating, rating);
+    }
+
+    let max_diff = RATING_DIFF_STEP * (EXPECTED_SCORES.len() as u32 - 1);
+    let diff = (rating - opponent_rating).min(max_diff);
+
+    let index = (diff / RATING_DIFF_STEP) as usize;
+    if index == EXPECTED_SCORES.len() - 1 {
+        return EXPECTED_SCORES[index];
+    }
+
+    // Linear interpolation between steps
+    let (low, high) = (EXPECTED_SCORES[index], EXPECTED_SCORES[index + 1]);
+    low + (high - low) * (diff % RATING_DIFF_STEP) / RATING_DIFF_STEP
+}
+
+/// Returns new ratings of both players, where `score_0` is
+/// `WIN_SCORE`, `DRAW_SCORE` or `LOSS_SCORE` of the first player.
+pub fn calculate_ratings(rating_0: u32, rating_1: u32, score_0: u32) -> (u32, u32) {
+    let expected_0 = expected_score(rating_0, rating_1);
+
+    // Rounded half away from zero, so the change is the same for both players
+    let change = K_FACTOR * (score_0 as i64 - expected_0 as i64);
+    let change = (change + change.signum() * 500) / 1000;
+
+    let rating_0 = (rating_0 as i64 + change).max(0) as u32;
+    let rating_1 = (rating_1 as i64 - change).max(0) as u32;
+
+    (rating_0, rating_1)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct PlayerStats {
+    pub rating: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+}
+
+impl Default for PlayerStats {
+    fn default() -> Self {
+        PlayerStats {
+            rating: INITIAL_RATING,
+            wins: 0,
+            losses: 0,
+            draws: 0,
+        }
+    }
+}
+
+impl PlayerStats {
+    /// Returns share of won games in basis points.
+    pub fn win_rate(&self) -> u32 {
+        let games = self.wins + self.losses + self.draws;
+        if games == 0 {
+            return 0;
+        }
+
+        (self.wins as u64 * 10_000 / games as u64) as u32
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum LeaderboardBy {
+    Rating,
+    Wins,
+    WinRate,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct LeaderboardEntry {
+    pub player: ActorId,
+    pub stats: PlayerStats,
+}
+
+/// Stats of rated players with rankings, which are kept sorted as games finish.
+#[derive(Debug, Default)]
+pub struct Leaderboard {
+    pub stats: BTreeMap<ActorId, PlayerStats>,
+    by_rating: BTreeSet<(u32, ActorId)>,
+    by_wins: BTreeSet<(u32, ActorId)>,
+    by_win_rate: BTreeSet<(u32, ActorId)>,
+}
+
+impl Leaderboard {
+    pub fn get_stats(&self, player: &ActorId) -> PlayerStats {
+        self.stats.get(player).cloned().unwrap_or_default()
+    }
+
+    /// Records `player` result of finished game, where `score` is
+    /// `WIN_SCORE`, `DRAW_SCORE` or `LOSS_SCORE`.
+    pub fn record(&mut self, player: ActorId, new_rating: u32, score: u32) {
+        let stats = self.stats.entry(player).or_default();
+
+        self.by_rating.remove(&(stats.rating, player));
+        self.by_wins.remove(&(stats.wins, player));
+        self.by_win_rate.remove(&(stats.win_rate(), player));
+
+        stats.rating = new_rating;
+        match score {
+            WIN_SCORE => stats.wins += 1,
+            DRAW_SCORE => stats.draws += 1,
+            _ => stats.losses += 1,
+        }
+
+        self.by_rating.insert((stats.rating, player));
+        self.by_wins.insert((stats.wins, player));
+        self.by_win_rate.insert((stats.win_rate(), player));
+    }
+
+    /// Returns page of players ranked from the best one.
+    pub fn get_page(&self, by: LeaderboardBy, offset: u64, limit: u64) -> Vec<LeaderboardEntry> {
+        let ranking = match by {
+            LeaderboardBy::Rating => &self.by_rating,
+            LeaderboardBy::Wins => &self.by_wins,
+            LeaderboardBy::WinRate => &self.by_win_rate,
+        };
+
+        ranking
+            .iter()
+            .rev()
+            .skip(usize::try_from(offset).unwrap_or(usize::MAX))
+            .take(usize::try_from(limit).unwrap_or(usize::MAX))
+            .map(|(_, player)| LeaderboardEntry {
+                player: *player,
+                stats: self.get_stats(player),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn success_expected_score() {
+        assert_eq!(expected_score(1200, 1200), 500);
+        assert_eq!(expected_score(1400, 1200), 760);
+        assert_eq!(expected_score(1200, 1400), 240);
+        assert_eq!(expected_score(1210, 1200), 514);
+        assert_eq!(expected_score(3000, 1000), 990);
+        assert_eq!(expected_score(1000, 3000), 10);
+    }
+
+    #[test]
+    fn success_calculate_ratings() {
+        assert_eq!(calculate_ratings(1200, 1200, WIN_SCORE), (1216, 1184));
+        assert_eq!(calculate_ratings(1200, 1200, LOSS_SCORE), (1184, 1216));
+        assert_eq!(calculate_ratings(1200, 1200, DRAW_SCORE), (1200, 1200));
+        assert_eq!(calculate_ratings(1400, 1200, WIN_SCORE), (1408, 1192));
+        assert_eq!(calculate_ratings(1400, 1200, DRAW_SCORE), (1392, 1208));
+        assert_eq!(calculate_ratings(1400, 1200, LOSS_SCORE), (1376, 1224));
+        assert_eq!(calculate_ratings(10, 2000, LOSS_SCORE), (10, 2000));
+    }
+
+    #[test]
+    fn success_leaderboard() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let player_1 = ActorId::new([1u8; 32]);
+        let player_2 = ActorId::new([2u8; 32]);
+        let mut leaderboard = Leaderboard::default();
+
+        leaderboard.record(player_0, 1216, WIN_SCORE);
+        leaderboard.record(player_1, 1184, LOSS_SCORE);
+        leaderboard.record(player_2, 1200, DRAW_SCORE);
+        leaderboard.record(player_1, 1201, WIN_SCORE);
+        leaderboard.record(player_1, 1190, DRAW_SCORE);
+
+        let ranked = |by| -> Vec<ActorId> {
+            leaderboard
+                .get_page(by, 0, 10)
+                .into_iter()
+                .map(|entry| entry.player)
+                .collect()
+        };
+
+        assert_eq!(
+            ranked(LeaderboardBy::Rating),
+            vec![player_0, player_2, player_1]
+        );
+        assert_eq!(
+            ranked(LeaderboardBy::WinRate),
+            vec![player_0, player_1, player_2]
+        );
+        assert_eq!(ranked(LeaderboardBy::Wins)[2], player_2);
+
+        assert_eq!(
+            leaderboard
+                .get_page(LeaderboardBy::Rating, 0, u64::MAX)
+                .len(),
+            3
+        );
+        assert!(leaderboard
+            .get_page(LeaderboardBy::Rating, u64::MAX, 10)
+            .is_empty());
+
+        let page = leaderboard.get_page(LeaderboardBy::Rating, 2, 10);
+        assert_eq!(
+            page,
+            vec![LeaderboardEntry {
+                player: player_1,
+                stats: PlayerStats {
+                    rating: 1190,
+                    wins: 1,
+                    losses: 1,
+                    draws: 1,
+                },
+            }]
+        );
+        assert_eq!(page[0].stats.win_rate(), 3333);
+        assert_eq!(
+            leaderboard.get_stats(&ActorId::new([3u8; 32])).rating,
+            INITIAL_RATING
+        );
+    }
+}
@@ -1,53 +1,456 @@
+use crate::{
+    bot::Difficulty,
+    coin_flip::{CoinFlip, Commitment, Secret},
+    error::TicTacToeError,
+    tournament::TournamentID,
+    ultimate::UltimateBoard,
+};
 use codec::{Decode, Encode};
 use gstd::{prelude::*, ActorId};
 
-pub const BOARD_SIZE: usize = 3;
+pub const MAX_BOARD_SIZE: u64 = 32;
+pub const INVITE_EXPIRATION_BLOCKS: u32 = 14_400;
+pub const DEFAULT_MOVE_TIMEOUT: u32 = 600;
+/// Number of blocks ended game is kept in full before it can be archived.
+pub const GAME_RETENTION_BLOCKS: u32 = 100_800;
+/// Maximum number of games archived on each game creation.
+pub const AUTO_PRUNE_LIMIT: u32 = 5;
+/// Maximum number of accounts notified about game events besides its players.
+pub const MAX_WATCHERS: usize = 16;
 pub type GameID = u128;
 
+/*
+    +++    +--    +--    --+
+    ---    +--    -+-    -+-
+    ---    +--    --+    +--
+*/
+pub(crate) const DIRECTIONS: [(isize, isize); 4] = [(1, 0), (0, 1), (1, 1), (-1, 1)];
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct Rules {
+    pub width: u64,
+    pub height: u64,
+    pub win_length: u64,
+    /// Number of blocks each player has to make a move.
+    pub move_timeout: u32,
+    /// Finish stale game without a claim from the waiting player.
+    pub auto_timeout: bool,
+    /// Decide who plays `BoardMark::X` by commit-reveal coin flip
+    /// instead of giving the first turn to the creator.
+    pub coin_flip: bool,
+    /// Completing a line loses instead of winning.
+    pub misere: bool,
+    /// Play on nine sub-boards, where each won sub-board is a mark of the 3x3 board.
+    pub ultimate: bool,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            width: 3,
+            height: 3,
+            win_length: 3,
+            move_timeout: DEFAULT_MOVE_TIMEOUT,
+            auto_timeout: false,
+            coin_flip: false,
+            misere: false,
+            ultimate: false,
+        }
+    }
+}
+
+impl Rules {
+    pub fn validate(&self) -> Result<(), TicTacToeError> {
+        let Rules {
+            width,
+            height,
+            win_length,
+            move_timeout,
+            auto_timeout: _,
+            coin_flip: _,
+            misere: _,
+            ultimate,
+        } = *self;
+
+        if ultimate && (width, height, win_length) != (3, 3, 3) {
+            return Err(TicTacToeError::InvalidUltimateRules);
+        }
+
+        if width == 0 || height == 0 || width > MAX_BOARD_SIZE || height > MAX_BOARD_SIZE {
+            return Err(TicTacToeError::InvalidBoardSize);
+        }
+
+        if win_length == 0 || win_length > width.max(height) {
+            return Err(TicTacToeError::InvalidWinLength);
+        }
+
+        if move_timeout == 0 {
+            return Err(TicTacToeError::InvalidMoveTimeout);
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
 pub enum BoardMark {
     X,
     O,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+impl BoardMark {
+    pub fn opposite(&self) -> BoardMark {
+        match self {
+            BoardMark::X => BoardMark::O,
+            BoardMark::O => BoardMark::X,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct Move {
+    pub player: ActorId,
+    pub x: u64,
+    pub y: u64,
+    pub mark: BoardMark,
+    pub block: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
 pub enum GameStatus {
+    Pending,
     Created,
     Canceled,
     Finished { winner: Option<ActorId> },
 }
 
-#[derive(Debug)]
+/// Everything needed to render the game in one query.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct GameView {
+    pub board: Vec<Vec<Option<BoardMark>>>,
+    pub win_length: u64,
+    pub player_0: ActorId,
+    pub player_1: Option<ActorId>,
+    pub player_0_mark: BoardMark,
+    pub player_1_mark: BoardMark,
+    pub status: GameStatus,
+    pub next_turn: (ActorId, BoardMark),
+    pub moves_count: u64,
+    pub stake: u128,
+    pub token: Option<ActorId>,
+    pub fee_bps: u16,
+    pub bot: Option<Difficulty>,
+    pub tournament: Option<TournamentID>,
+    pub coin_flip: Option<CoinFlip>,
+    pub misere: bool,
+    pub ultimate: Option<UltimateBoard>,
+}
+
+/// Result of ended game, which is kept instead of the game after the retention period.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct ArchivedGame {
+    pub player_0: ActorId,
+    pub player_1: Option<ActorId>,
+    /// Either `GameStatus::Canceled` or `GameStatus::Finished`.
+    pub status: GameStatus,
+    pub ended_at: u32,
+}
+
+impl ArchivedGame {
+    pub fn get_winner(&self) -> Option<ActorId> {
+        match self.status {
+            GameStatus::Finished { winner } => winner,
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct Game {
-    pub board: [[Option<BoardMark>; BOARD_SIZE]; BOARD_SIZE],
+    pub board: Vec<Vec<Option<BoardMark>>>,
+    pub width: usize,
+    pub height: usize,
+    pub win_length: usize,
     pub player_0: ActorId,
-    pub player_1: ActorId,
+    pub player_1: Option<ActorId>,
     pub next_turn: (ActorId, BoardMark),
     pub player_to_board_mark: BTreeMap<ActorId, BoardMark>,
     pub status: GameStatus,
+    pub stake: u128,
+    pub pot: u128,
+    /// Fungible token program of the stake, native value if `None`.
+    pub token: Option<ActorId>,
+    /// Platform fee taken from the pot of finished game, fixed at creation.
+    pub fee_bps: u16,
+    /// Player whose token deposit is being transferred,
+    /// the game can't be entered or canceled until it's confirmed.
+    pub deposit_lock: Option<ActorId>,
+    pub created_at: u32,
+    pub move_timeout: u32,
+    pub auto_timeout: bool,
+    pub last_move_at: u32,
+    pub bot: Option<Difficulty>,
+    pub tournament: Option<TournamentID>,
+    /// Player who completes a line loses.
+    pub misere: bool,
+    /// Sub-boards of ultimate game, `board` holds their winners.
+    pub ultimate: Option<UltimateBoard>,
+    /// Coin flip in progress, the game can't be played until it's finished.
+    pub coin_flip: Option<CoinFlip>,
+    /// Player who asked to cancel the started game, until the next move.
+    pub cancel_offer: Option<ActorId>,
+    /// Player who offered a draw, until their next move.
+    pub draw_offer: Option<ActorId>,
+    /// Player who asked to undo their last move, until the next move.
+    pub takeback_request: Option<ActorId>,
+    pub watchers: BTreeSet<ActorId>,
+    /// Watchers removed by the players, who can't watch the game again.
+    pub kicked_watchers: BTreeSet<ActorId>,
+    pub moves: Vec<Move>,
 }
 
 impl Game {
-    pub fn init(player_0: ActorId, player_1: ActorId) -> Self {
-        if player_0 == player_1 {
-            panic!("You must have friends ;(");
+    pub fn init(
+        player_0: ActorId,
+        player_1: Option<ActorId>,
+        rules: Rules,
+        stake: u128,
+        created_at: u32,
+    ) -> Result<Self, TicTacToeError> {
+        if player_1 == Some(player_0) {
+            return Err(TicTacToeError::SamePlayers);
         }
 
-        // Custom first turn logic can be applied here:
+        rules.validate()?;
+
+        let Rules {
+            width,
+            height,
+            win_length,
+            move_timeout,
+            auto_timeout,
+            coin_flip,
+            misere,
+            ultimate,
+        } = rules;
+
+        // Coin flip may give the first turn to `player_1` later
         let next_turn = (player_0, BoardMark::X);
 
         let mut player_to_board_mark = BTreeMap::new();
         player_to_board_mark.insert(player_0, BoardMark::X);
-        player_to_board_mark.insert(player_1, BoardMark::O);
+        if let Some(player_1) = player_1 {
+            player_to_board_mark.insert(player_1, BoardMark::O);
+        }
 
-        Game {
-            board: Default::default(),
+        Ok(Game {
+            board: vec![vec![None; width as usize]; height as usize],
+            width: width as usize,
+            height: height as usize,
+            win_length: win_length as usize,
             player_0,
             player_1,
             next_turn,
             player_to_board_mark,
-            status: GameStatus::Created,
+            status: GameStatus::Pending,
+            stake,
+            pot: stake,
+            token: None,
+            fee_bps: 0,
+            deposit_lock: None,
+            created_at,
+            move_timeout,
+            auto_timeout,
+            last_move_at: created_at,
+            bot: None,
+            tournament: None,
+            misere,
+            ultimate: ultimate.then(UltimateBoard::default),
+            coin_flip: coin_flip.then(CoinFlip::default),
+            cancel_offer: None,
+            draw_offer: None,
+            takeback_request: None,
+            watchers: BTreeSet::new(),
+            kicked_watchers: BTreeSet::new(),
+            moves: Vec::new(),
+        })
+    }
+
+    /// Creates game against the program itself,
+    /// where `bot` replies to each `player_0` turn.
+    pub fn init_vs_bot(
+        player_0: ActorId,
+        bot: ActorId,
+        difficulty: Difficulty,
+        rules: Rules,
+        created_at: u32,
+    ) -> Result<Self, TicTacToeError> {
+        if rules.coin_flip {
+            return Err(TicTacToeError::BotGameWithCoinFlip);
+        }
+
+        if rules.ultimate {
+            return Err(TicTacToeError::BotGameWithUltimate);
+        }
+
+        let mut game = Game::init(player_0, Some(bot), rules, 0, created_at)?;
+        game.bot = Some(difficulty);
+        game.start(0, created_at)?;
+
+        Ok(game)
+    }
+
+    /// Creates started `tournament` match game without a stake.
+    pub fn init_for_tournament(
+        player_0: ActorId,
+        player_1: ActorId,
+        rules: Rules,
+        tournament: TournamentID,
+        created_at: u32,
+    ) -> Result<Self, TicTacToeError> {
+        let mut game = Game::init(player_0, Some(player_1), rules, 0, created_at)?;
+        game.tournament = Some(tournament);
+        game.start(0, created_at)?;
+
+        Ok(game)
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.status == GameStatus::Pending
+    }
+
+    /// Returns `true` if pending game has an empty seat for anyone to join.
+    pub fn is_open(&self) -> bool {
+        self.is_pending() && self.player_1.is_none()
+    }
+
+    /// Returns `true` if pending invite can't be accepted anymore at `now` block.
+    ///
+    /// Open games never expire.
+    pub fn is_invite_expired(&self, now: u32) -> bool {
+        self.is_pending()
+            && self.player_1.is_some()
+            && now > self.created_at.saturating_add(INVITE_EXPIRATION_BLOCKS)
+    }
+
+    /// Cancels the invite nobody accepted in time at `now` block.
+    ///
+    /// Returns `true` if the invite is canceled.
+    pub fn expire_invite(&mut self, now: u32) -> bool {
+        if !self.is_invite_expired(now) || self.deposit_lock.is_some() {
+            return false;
+        }
+
+        self.status = GameStatus::Canceled;
+        true
+    }
+
+    /// Handle `player` accepting the invite with `value` matching the game stake.
+    pub fn accept(
+        &mut self,
+        player: &ActorId,
+        value: u128,
+        now: u32,
+    ) -> Result<(), TicTacToeError> {
+        self.assert_pending_invite(player)?;
+
+        if self.is_invite_expired(now) {
+            return Err(TicTacToeError::InviteExpired);
+        }
+
+        self.start(value, now)
+    }
+
+    /// Handle `player` taking the empty seat with `value` matching the game stake.
+    pub fn join(&mut self, player: &ActorId, value: u128, now: u32) -> Result<(), TicTacToeError> {
+        if !self.is_open() {
+            return Err(TicTacToeError::GameNotOpen);
+        }
+
+        if player == &self.player_0 {
+            return Err(TicTacToeError::SamePlayers);
         }
+
+        self.start(value, now)?;
+
+        self.player_1 = Some(*player);
+        self.player_to_board_mark.insert(*player, BoardMark::O);
+
+        Ok(())
+    }
+
+    /// Reserves the game for `player` while their token deposit is transferred.
+    pub fn lock_deposit(&mut self, player: &ActorId) -> Result<(), TicTacToeError> {
+        self.assert_no_deposit_lock()?;
+
+        self.deposit_lock = Some(*player);
+        Ok(())
+    }
+
+    /// Releases the game after the deposit transfer is confirmed or failed.
+    pub fn unlock_deposit(&mut self) {
+        self.deposit_lock = None;
+    }
+
+    fn start(&mut self, value: u128, now: u32) -> Result<(), TicTacToeError> {
+        self.assert_no_deposit_lock()?;
+
+        if value != self.stake {
+            return Err(TicTacToeError::InvalidStake);
+        }
+
+        self.pot += value;
+        self.status = GameStatus::Created;
+        self.last_move_at = now;
+
+        Ok(())
+    }
+
+    /// Handle `player` declining the invite.
+    pub fn decline(&mut self, player: &ActorId) -> Result<(), TicTacToeError> {
+        self.assert_pending_invite(player)?;
+        self.assert_no_deposit_lock()?;
+
+        self.status = GameStatus::Canceled;
+        Ok(())
+    }
+
+    /// Empties the pot of ended game.
+    ///
+    /// Returns payouts of the game, where:
+    ///
+    /// - Winner takes the whole pot.
+    ///
+    /// - Pot is split on a tie.
+    ///
+    /// - Deposits are refunded on cancel.
+    pub fn settle(&mut self) -> Vec<(ActorId, u128)> {
+        let pot = self.pot;
+
+        let payouts = match self.status {
+            GameStatus::Finished {
+                winner: Some(winner),
+            } => vec![(winner, pot)],
+            GameStatus::Finished { winner: None } => vec![
+                (self.player_0, pot / 2),
+                (self.player_1.expect("Invalid data"), pot - pot / 2),
+            ],
+            GameStatus::Canceled => {
+                let mut refunds = vec![(self.player_0, pot.min(self.stake))];
+                if let Some(player_1) = self.player_1 {
+                    refunds.push((player_1, pot.saturating_sub(self.stake)));
+                }
+
+                refunds
+            }
+            GameStatus::Pending | GameStatus::Created => panic!("Game is not ended!"),
+        };
+
+        self.pot = 0;
+        payouts
+            .into_iter()
+            .filter(|(_, amount)| *amount > 0)
+            .collect()
     }
 
     pub fn is_ended(&self) -> bool {
@@ -58,6 +461,11 @@ impl Game {
     }
 
     pub fn is_board_filled(&self) -> bool {
+        // Drawn sub-boards leave empty cells on the main board
+        if let Some(ultimate) = &self.ultimate {
+            return ultimate.is_filled();
+        }
+
         for y_axis in &self.board {
             for x_axis in y_axis {
                 if x_axis.is_none() {
@@ -97,7 +505,7 @@ impl Game {
         let (last_player, last_board_mark) = &self.next_turn;
 
         let next_player = if last_player == &self.player_0 {
-            self.player_1
+            self.player_1.expect("Invalid data")
         } else {
             self.player_0
         };
@@ -111,6 +519,35 @@ impl Game {
         (next_player, next_board_mark)
     }
 
+    pub fn get_view(&self) -> GameView {
+        let player_0_mark = self.get_board_mark(&self.player_0);
+        let player_1_mark = if player_0_mark == BoardMark::X {
+            BoardMark::O
+        } else {
+            BoardMark::X
+        };
+
+        GameView {
+            board: self.board.clone(),
+            win_length: self.win_length as u64,
+            player_0: self.player_0,
+            player_1: self.player_1,
+            player_0_mark,
+            player_1_mark,
+            status: self.status.clone(),
+            next_turn: self.next_turn.clone(),
+            moves_count: self.moves.len() as u64,
+            stake: self.stake,
+            token: self.token,
+            fee_bps: self.fee_bps,
+            bot: self.bot,
+            tournament: self.tournament,
+            coin_flip: self.coin_flip.clone(),
+            misere: self.misere,
+            ultimate: self.ultimate.clone(),
+        }
+    }
+
     pub fn get_winner(&self) -> Option<ActorId> {
         match self.status {
             GameStatus::Finished { winner } => winner,
@@ -118,110 +555,109 @@ impl Game {
         }
     }
 
-    pub fn check_winner_row(
-        &self,
-        x_indexes: [usize; BOARD_SIZE],
-        y_indexes: [usize; BOARD_SIZE],
-    ) -> Option<BoardMark> {
-        if self.board[y_indexes[0]][x_indexes[0]].is_some()
-            && self.board[y_indexes[1]][x_indexes[1]].is_some()
-            && self.board[y_indexes[2]][x_indexes[2]].is_some()
-        {
-            let a = self.board[y_indexes[0]][x_indexes[0]].as_ref().unwrap();
-            let b = self.board[y_indexes[1]][x_indexes[1]].as_ref().unwrap();
-            let c = self.board[y_indexes[2]][x_indexes[2]].as_ref().unwrap();
-
-            if a == b && a == c {
-                return Some(a.clone());
+    /// Returns result record of the game ended at `ended_at` block.
+    pub fn archive(&self, ended_at: u32) -> ArchivedGame {
+        ArchivedGame {
+            player_0: self.player_0,
+            player_1: self.player_1,
+            status: self.status.clone(),
+            ended_at,
+        }
+    }
+
+    /// Returns mark which occupies `win_length` consecutive cells
+    /// starting from (`x`, `y`) cell in (`dx`, `dy`) direction.
+    pub fn check_winner_line(&self, x: usize, y: usize, dx: isize, dy: isize) -> Option<BoardMark> {
+        let mark = self.board.get(y)?.get(x)?.as_ref()?;
+
+        for step in 1..self.win_length as isize {
+            let x_index = x as isize + dx * step;
+            let y_index = y as isize + dy * step;
+
+            if x_index < 0 || y_index < 0 {
+                return None;
+            }
+
+            let cell = self
+                .board
+                .get(y_index as usize)
+                .and_then(|y_axis| y_axis.get(x_index as usize))?;
+
+            if cell.as_ref() != Some(mark) {
+                return None;
             }
         }
 
-        None
+        Some(mark.clone())
     }
 
+    /// Returns mark of the winner, which is the mark that completed
+    /// a line or its opposite in misère game.
     pub fn check_winner(&self) -> Option<BoardMark> {
-        /*
-            +++
-            ---
-            ---
-        */
-        let res = self.check_winner_row([0, 1, 2], [0, 0, 0]);
-        if res.is_some() {
-            return res;
-        }
-
-        /*
-            ---
-            +++
-            ---
-        */
-        let res = self.check_winner_row([0, 1, 2], [1, 1, 1]);
-        if res.is_some() {
-            return res;
-        }
-
-        /*
-            ---
-            ---
-            +++
-        */
-        let res = self.check_winner_row([0, 1, 2], [2, 2, 2]);
-        if res.is_some() {
-            return res;
-        }
-
-        /*
-            +--
-            +--
-            +--
-        */
-        let res = self.check_winner_row([0, 0, 0], [0, 1, 2]);
-        if res.is_some() {
-            return res;
-        }
-
-        /*
-            -+-
-            -+-
-            -+-
-        */
-        let res = self.check_winner_row([1, 1, 1], [0, 1, 2]);
-        if res.is_some() {
-            return res;
-        }
-
-        /*
-            --+
-            --+
-            --+
-        */
-        let res = self.check_winner_row([2, 2, 2], [0, 1, 2]);
-        if res.is_some() {
-            return res;
-        }
-
-        /*
-            --+
-            -+-
-            +--
-        */
-        let res = self.check_winner_row([2, 1, 0], [0, 1, 2]);
-        if res.is_some() {
-            return res;
-        }
-
-        /*
-            +--
-            -+-
-            --+
-        */
-        self.check_winner_row([0, 1, 2], [0, 1, 2])
+        for y in 0..self.height {
+            for x in 0..self.width {
+                for (dx, dy) in DIRECTIONS {
+                    if let Some(mark) = self.check_winner_line(x, y, dx, dy) {
+                        return Some(if self.misere { mark.opposite() } else { mark });
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Returns `true` if mark at (`x`, `y`) cell is a part of
+    /// `win_length` consecutive cells in any direction.
+    pub fn is_winning_cell(&self, x: usize, y: usize) -> bool {
+        let mark = match self.board.get(y).and_then(|y_axis| y_axis.get(x)) {
+            Some(Some(mark)) => mark,
+            _ => return false,
+        };
+
+        DIRECTIONS.iter().any(|&(dx, dy)| {
+            let forward = self.count_marks(x, y, dx, dy, mark);
+            let backward = self.count_marks(x, y, -dx, -dy, mark);
+
+            1 + forward + backward >= self.win_length
+        })
+    }
+
+    /// Returns number of consecutive `mark` cells after (`x`, `y`)
+    /// cell in (`dx`, `dy`) direction.
+    pub(crate) fn count_marks(
+        &self,
+        x: usize,
+        y: usize,
+        dx: isize,
+        dy: isize,
+        mark: &BoardMark,
+    ) -> usize {
+        let mut count = 0;
+        let (mut x_index, mut y_index) = (x as isize + dx, y as isize + dy);
+
+        while x_index >= 0 && y_index >= 0 {
+            let cell = self
+                .board
+                .get(y_index as usize)
+                .and_then(|y_axis| y_axis.get(x_index as usize));
+
+            if cell != Some(&Some(mark.clone())) {
+                break;
+            }
+
+            count += 1;
+            x_index += dx;
+            y_index += dy;
+        }
+
+        count
     }
 
     /// Returns condition which indicates
     /// end of the game, when:
     ///
-    /// - Win combination is found.
+    /// - Line is completed, which loses in misère game.
     ///
     /// - Game board is filled.
     fn handle_game_round(&mut self) -> bool {
@@ -244,86 +680,552 @@ impl Game {
         false
     }
 
+    /// Returns `true` if current player missed the move deadline at `now` block.
+    pub fn is_move_expired(&self, now: u32) -> bool {
+        self.status == GameStatus::Created
+            && now > self.last_move_at.saturating_add(self.move_timeout)
+    }
+
+    /// Finish the game in favor of waiting player, when current player
+    /// missed the move deadline.
+    ///
+    /// Returns `true` if game is finished.
+    pub fn expire(&mut self, now: u32) -> bool {
+        if self.coin_flip.is_some() || !self.is_move_expired(now) {
+            return false;
+        }
+
+        let (waiting_player, _) = self.get_next_turn();
+
+        self.status = GameStatus::Finished {
+            winner: Some(waiting_player),
+        };
+        true
+    }
+
+    /// Handle `player` commitment to the coin flip secret.
+    pub fn commit_flip(
+        &mut self,
+        player: &ActorId,
+        commitment: Commitment,
+        now: u32,
+    ) -> Result<(), TicTacToeError> {
+        self.get_coin_flip_mut(player)?.commit(player, commitment)?;
+
+        self.last_move_at = now;
+        Ok(())
+    }
+
+    /// Handle `player` revealing the coin flip secret.
+    ///
+    /// Returns `true` if the first turn is decided.
+    pub fn reveal_flip(
+        &mut self,
+        player: &ActorId,
+        secret: Secret,
+        now: u32,
+    ) -> Result<bool, TicTacToeError> {
+        let (player_0, player_1) = (self.player_0, self.player_1);
+
+        let coin_flip = self.get_coin_flip_mut(player)?;
+        coin_flip.reveal(player, secret)?;
+
+        let winner = coin_flip.get_winner(player_0, player_1.expect("Invalid data"));
+
+        self.last_move_at = now;
+        if let Some(winner) = winner {
+            self.set_first_turn(winner);
+        }
+
+        Ok(winner.is_some())
+    }
+
+    /// Finish the coin flip after the deadline, where the player who made more
+    /// steps of the protocol plays first, or the creator does on a tie.
+    ///
+    /// Returns `true` if the first turn is decided.
+    pub fn expire_flip(&mut self, now: u32) -> bool {
+        let coin_flip = match &self.coin_flip {
+            Some(coin_flip) if self.is_move_expired(now) => coin_flip,
+            _ => return false,
+        };
+
+        let player_1 = self.player_1.expect("Invalid data");
+        let first_player = if coin_flip.progress(&player_1) > coin_flip.progress(&self.player_0) {
+            player_1
+        } else {
+            self.player_0
+        };
+
+        self.last_move_at = now;
+        self.set_first_turn(first_player);
+        true
+    }
+
+    /// Handle `player` claiming the first turn after the opponent
+    /// missed the coin flip deadline.
+    pub fn claim_flip_timeout(&mut self, player: &ActorId, now: u32) -> Result<(), TicTacToeError> {
+        let (player_0, player_1) = (self.player_0, self.player_1);
+        let coin_flip = self.get_coin_flip_mut(player)?;
+
+        let opponent = if player == &player_0 {
+            player_1.expect("Invalid data")
+        } else {
+            player_0
+        };
+
+        if coin_flip.progress(player) <= coin_flip.progress(&opponent) {
+            return Err(TicTacToeError::CoinFlipNotAhead);
+        }
+
+        if !self.expire_flip(now) {
+            return Err(TicTacToeError::MoveDeadlineNotPassed);
+        }
+
+        Ok(())
+    }
+
+    fn get_coin_flip_mut(&mut self, player: &ActorId) -> Result<&mut CoinFlip, TicTacToeError> {
+        self.assert_not_ended()?;
+        self.assert_player_in_game(player)?;
+
+        if self.is_pending() {
+            return Err(TicTacToeError::GameNotStarted);
+        }
+
+        self.coin_flip.as_mut().ok_or(TicTacToeError::NoCoinFlip)
+    }
+
+    /// Finishes the coin flip giving `player` the first turn and `BoardMark::X`.
+    fn set_first_turn(&mut self, player: ActorId) {
+        let opponent = if player == self.player_0 {
+            self.player_1.expect("Invalid data")
+        } else {
+            self.player_0
+        };
+
+        self.player_to_board_mark.insert(player, BoardMark::X);
+        self.player_to_board_mark.insert(opponent, BoardMark::O);
+        self.next_turn = (player, BoardMark::X);
+        self.coin_flip = None;
+    }
+
+    /// Handle waiting `player` claiming victory after the move deadline.
+    pub fn claim_timeout(&mut self, player: &ActorId, now: u32) -> Result<(), TicTacToeError> {
+        self.assert_not_ended()?;
+        self.assert_player_in_game(player)?;
+
+        if self.is_pending() {
+            return Err(TicTacToeError::GameNotStarted);
+        }
+
+        if self.coin_flip.is_some() {
+            return Err(TicTacToeError::CoinFlipNotFinished);
+        }
+
+        if player == &self.next_turn.0 {
+            return Err(TicTacToeError::YourTurn);
+        }
+
+        if !self.expire(now) {
+            return Err(TicTacToeError::MoveDeadlineNotPassed);
+        }
+
+        Ok(())
+    }
+
     /// Handle current `player` turn.
     ///
     /// Returns `true` if game is finished at this turn.
-    pub fn turn(&mut self, player: &ActorId, x: usize, y: usize) -> bool {
-        self.assert_not_ended();
-        self.assert_player_in_game(player);
+    pub fn turn(
+        &mut self,
+        player: &ActorId,
+        x: usize,
+        y: usize,
+        now: u32,
+    ) -> Result<bool, TicTacToeError> {
+        if self.ultimate.is_some() {
+            return Err(TicTacToeError::UltimateTurnRequired);
+        }
+
+        self.make_move(player, now, |game, mark| {
+            let x_cell = game
+                .board
+                .get_mut(y)
+                .and_then(|y_axis| y_axis.get_mut(x))
+                .ok_or(TicTacToeError::OutOfBoard)?;
+
+            if x_cell.is_some() {
+                return Err(TicTacToeError::LocationNotEmpty);
+            }
+
+            *x_cell = Some(mark.clone());
+            Ok((x, y))
+        })
+    }
+
+    /// Handle `player` move to `cell` of ultimate game sub-`board`.
+    ///
+    /// Returns `true` if game is finished.
+    pub fn ultimate_turn(
+        &mut self,
+        player: &ActorId,
+        board: usize,
+        cell: usize,
+        now: u32,
+    ) -> Result<bool, TicTacToeError> {
+        if self.ultimate.is_none() {
+            return Err(TicTacToeError::NotUltimateGame);
+        }
+
+        self.make_move(player, now, |game, mark| {
+            let ultimate = game.ultimate.as_mut().expect("Invalid data");
+
+            if ultimate.place(board, cell, mark)? {
+                game.board[board / 3][board % 3] = Some(mark.clone());
+            }
+
+            Ok(UltimateBoard::to_grid(board, cell))
+        })
+    }
+
+    /// Makes `player` move, where `place` puts the mark on the board
+    /// and returns (`x`, `y`) coordinates of the move.
+    ///
+    /// Returns `true` if game is finished.
+    fn make_move(
+        &mut self,
+        player: &ActorId,
+        now: u32,
+        place: impl FnOnce(&mut Self, &BoardMark) -> Result<(usize, usize), TicTacToeError>,
+    ) -> Result<bool, TicTacToeError> {
+        self.assert_not_ended()?;
+        self.assert_player_in_game(player)?;
 
         let (current_player, current_mark) = self.next_turn.clone();
 
+        if self.is_pending() {
+            return Err(TicTacToeError::GameNotStarted);
+        }
+
+        if self.coin_flip.is_some() {
+            return Err(TicTacToeError::CoinFlipNotFinished);
+        }
+
         // 1. Handle possible ending state before turn
         if self.handle_game_round() {
-            return true;
+            return Ok(true);
         }
 
         // 2. Check if `player` can do current turn
         if player != &current_player {
-            panic!("It's not your turn!");
+            return Err(TicTacToeError::NotYourTurn);
         }
 
         // 3. Place `player` mark
-        let y_cell = self.board.get_mut(y).expect("Invalid y index!");
-        let x_cell = y_cell.get_mut(x).expect("Invalid x index!");
-
-        if x_cell.is_some() {
-            panic!("Location is not empty!");
+        let (x, y) = place(self, &current_mark)?;
+        self.last_move_at = now;
+        self.cancel_offer = None;
+        self.takeback_request = None;
+        if self.draw_offer == Some(current_player) {
+            self.draw_offer = None;
         }
-
-        *x_cell = Some(current_mark);
+        self.moves.push(Move {
+            player: current_player,
+            x: x as u64,
+            y: y as u64,
+            mark: current_mark,
+            block: now,
+        });
 
         // 4. Handle possible ending state after turn
         if self.handle_game_round() {
-            return true;
+            return Ok(true);
         }
 
         // 5. Update next turn
         self.next_turn = self.get_next_turn();
-        false
+        Ok(false)
     }
 
-    fn assert_not_ended(&self) {
+    fn assert_not_ended(&self) -> Result<(), TicTacToeError> {
         if self.is_ended() {
-            panic!("Game is ended!");
+            return Err(TicTacToeError::GameEnded);
         }
-    }
 
-    fn assert_player_in_game(&self, player: &ActorId) {
-        if &self.player_0 != player && &self.player_1 != player {
-            panic!("Player not found in this game!");
-        }
+        Ok(())
     }
 
-    pub fn cancel(&mut self, player: &ActorId) {
-        self.assert_not_ended();
-        self.assert_player_in_game(player);
+    /// Checks that `player` takes part in the started game, which is not ended.
+    fn assert_started(&self, player: &ActorId) -> Result<(), TicTacToeError> {
+        self.assert_not_ended()?;
+        self.assert_player_in_game(player)?;
 
-        self.status = GameStatus::Canceled;
+        if self.is_pending() {
+            return Err(TicTacToeError::GameNotStarted);
+        }
+
+        Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use gstd::ActorId;
+    fn assert_pending_invite(&self, player: &ActorId) -> Result<(), TicTacToeError> {
+        if !self.is_pending() {
+            return Err(TicTacToeError::GameNotPending);
+        }
 
-    fn setup() -> (ActorId, ActorId, Game) {
-        let player_0 = ActorId::new([0u8; 32]);
-        let player_1 = ActorId::new([1u8; 32]);
+        if self.player_1.as_ref() != Some(player) {
+            return Err(TicTacToeError::InviteNotAddressedToYou);
+        }
 
-        (player_0, player_1, Game::init(player_0, player_1))
+        Ok(())
     }
 
-    #[test]
-    fn success_init() {
-        let (player_0, player_1, game) = setup();
+    pub fn watch(&mut self, watcher: &ActorId) -> Result<(), TicTacToeError> {
+        self.assert_not_ended()?;
 
-        assert_eq!(game.player_0, player_0);
-        assert_eq!(game.player_1, player_1);
-        assert_eq!(game.next_turn, (player_0, BoardMark::X));
-        assert_eq!(game.status, GameStatus::Created);
-    }
+        if self.watchers.contains(watcher) {
+            return Err(TicTacToeError::AlreadyWatching);
+        }
+
+        if self.kicked_watchers.contains(watcher) {
+            return Err(TicTacToeError::KickedWatcher);
+        }
+
+        if self.watchers.len() >= MAX_WATCHERS {
+            return Err(TicTacToeError::TooManyWatchers);
+        }
+
+        self.watchers.insert(*watcher);
+        Ok(())
+    }
+
+    pub fn unwatch(&mut self, watcher: &ActorId) -> Result<(), TicTacToeError> {
+        if !self.watchers.remove(watcher) {
+            return Err(TicTacToeError::NotWatching);
+        }
+
+        Ok(())
+    }
+
+    /// Handle `player` removing `watcher` from the game for good.
+    pub fn kick_watcher(
+        &mut self,
+        player: &ActorId,
+        watcher: &ActorId,
+    ) -> Result<(), TicTacToeError> {
+        self.assert_player_in_game(player)?;
+        self.unwatch(watcher)?;
+
+        self.kicked_watchers.insert(*watcher);
+        Ok(())
+    }
+
+    /// Returns players and watchers of the game.
+    pub fn get_audience(&self) -> BTreeSet<ActorId> {
+        let mut audience = self.watchers.clone();
+        audience.insert(self.player_0);
+        audience.extend(self.player_1);
+
+        audience
+    }
+
+    fn assert_no_deposit_lock(&self) -> Result<(), TicTacToeError> {
+        if self.deposit_lock.is_some() {
+            return Err(TicTacToeError::DepositInProgress);
+        }
+
+        Ok(())
+    }
+
+    fn assert_player_in_game(&self, player: &ActorId) -> Result<(), TicTacToeError> {
+        if &self.player_0 != player && self.player_1.as_ref() != Some(player) {
+            return Err(TicTacToeError::PlayerNotInGame);
+        }
+
+        Ok(())
+    }
+
+    /// Handle `player` canceling the game, which is allowed before the first
+    /// move, otherwise both players have to ask for it.
+    ///
+    /// Returns `true` if game is canceled.
+    pub fn cancel(&mut self, player: &ActorId) -> Result<bool, TicTacToeError> {
+        self.assert_not_ended()?;
+        self.assert_player_in_game(player)?;
+        self.assert_no_deposit_lock()?;
+
+        if self.tournament.is_some() {
+            return Err(TicTacToeError::TournamentGameCantBeCanceled);
+        }
+
+        if !self.moves.is_empty() {
+            match self.cancel_offer {
+                Some(offering_player) if &offering_player == player => {
+                    return Err(TicTacToeError::CancelAlreadyOffered);
+                }
+                Some(_) => (),
+                None => {
+                    self.cancel_offer = Some(*player);
+                    return Ok(false);
+                }
+            }
+        }
+
+        self.status = GameStatus::Canceled;
+        Ok(true)
+    }
+
+    pub fn offer_draw(&mut self, player: &ActorId) -> Result<(), TicTacToeError> {
+        self.assert_started(player)?;
+
+        if self.draw_offer.is_some() {
+            return Err(TicTacToeError::DrawAlreadyOffered);
+        }
+
+        self.draw_offer = Some(*player);
+        Ok(())
+    }
+
+    /// Handle `player` accepting the opponent draw offer.
+    pub fn accept_draw(&mut self, player: &ActorId) -> Result<(), TicTacToeError> {
+        self.assert_draw_offered_to(player)?;
+
+        self.status = GameStatus::Finished { winner: None };
+        Ok(())
+    }
+
+    /// Handle `player` declining the opponent draw offer.
+    pub fn decline_draw(&mut self, player: &ActorId) -> Result<(), TicTacToeError> {
+        self.assert_draw_offered_to(player)?;
+
+        self.draw_offer = None;
+        Ok(())
+    }
+
+    fn assert_draw_offered_to(&self, player: &ActorId) -> Result<(), TicTacToeError> {
+        self.assert_started(player)?;
+
+        match self.draw_offer {
+            Some(offering_player) if &offering_player != player => Ok(()),
+            _ => Err(TicTacToeError::NoDrawOffer),
+        }
+    }
+
+    /// Handle `player` asking the opponent to undo their last move.
+    pub fn request_takeback(&mut self, player: &ActorId) -> Result<(), TicTacToeError> {
+        self.assert_started(player)?;
+
+        if self.tournament.is_some() {
+            return Err(TicTacToeError::TakebackInTournament);
+        }
+
+        if self.ultimate.is_some() {
+            return Err(TicTacToeError::TakebackInUltimate);
+        }
+
+        if self.moves.last().map(|last_move| &last_move.player) != Some(player) {
+            return Err(TicTacToeError::NoMoveToTakeBack);
+        }
+
+        if self.takeback_request.is_some() {
+            return Err(TicTacToeError::TakebackAlreadyRequested);
+        }
+
+        self.takeback_request = Some(*player);
+        Ok(())
+    }
+
+    /// Handle `player` approving the opponent takeback request,
+    /// so the last move is undone and it's the opponent turn again.
+    ///
+    /// Returns the undone move.
+    pub fn approve_takeback(&mut self, player: &ActorId, now: u32) -> Result<Move, TicTacToeError> {
+        self.assert_started(player)?;
+
+        match self.takeback_request {
+            Some(requesting_player) if &requesting_player != player => (),
+            _ => return Err(TicTacToeError::NoTakebackRequest),
+        }
+
+        let last_move = self.moves.pop().expect("Invalid data");
+        self.board[last_move.y as usize][last_move.x as usize] = None;
+        self.next_turn = (last_move.player, last_move.mark.clone());
+        self.last_move_at = now;
+        self.takeback_request = None;
+
+        Ok(last_move)
+    }
+
+    /// Handle `player` giving up the started game to the opponent.
+    pub fn resign(&mut self, player: &ActorId) -> Result<(), TicTacToeError> {
+        self.assert_started(player)?;
+
+        let opponent = if player == &self.player_0 {
+            self.player_1.expect("Invalid data")
+        } else {
+            self.player_0
+        };
+
+        self.status = GameStatus::Finished {
+            winner: Some(opponent),
+        };
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coin_flip::commitment;
+    use gstd::ActorId;
+
+    fn rules(width: u64, height: u64, win_length: u64) -> Rules {
+        Rules {
+            width,
+            height,
+            win_length,
+            ..Default::default()
+        }
+    }
+
+    fn setup_wager(stake: u128) -> (ActorId, ActorId, Game) {
+        let player_0 = ActorId::new([0u8; 32]);
+        let player_1 = ActorId::new([1u8; 32]);
+        let mut game = Game::init(player_0, Some(player_1), Rules::default(), stake, 0).unwrap();
+        game.accept(&player_1, stake, 0).unwrap();
+
+        (player_0, player_1, game)
+    }
+
+    fn setup() -> (ActorId, ActorId, Game) {
+        setup_wager(0)
+    }
+
+    fn setup_with_rules(rules: Rules) -> (ActorId, ActorId, Game) {
+        let player_0 = ActorId::new([0u8; 32]);
+        let player_1 = ActorId::new([1u8; 32]);
+        let mut game = Game::init(player_0, Some(player_1), rules, 0, 0).unwrap();
+        game.accept(&player_1, 0, 0).unwrap();
+
+        (player_0, player_1, game)
+    }
+
+    fn setup_coin_flip() -> (ActorId, ActorId, Game) {
+        setup_with_rules(Rules {
+            coin_flip: true,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn success_init() {
+        let (player_0, player_1, game) = setup();
+
+        assert_eq!(game.player_0, player_0);
+        assert_eq!(game.player_1, Some(player_1));
+        assert_eq!(game.next_turn, (player_0, BoardMark::X));
+        assert_eq!(game.status, GameStatus::Created);
+    }
 
     #[test]
     fn success_turn_handle_game_round_winner() {
@@ -331,23 +1233,23 @@ mod tests {
         assert_eq!(game.next_turn, (player_0, BoardMark::X));
         assert_eq!(game.get_next_turn(), (player_1, BoardMark::O));
 
-        game.turn(&player_0, 0, 0);
+        game.turn(&player_0, 0, 0, 0).unwrap();
         assert_eq!(game.next_turn, (player_1, BoardMark::O));
         assert_eq!(game.get_next_turn(), (player_0, BoardMark::X));
 
-        game.turn(&player_1, 2, 2);
+        game.turn(&player_1, 2, 2, 0).unwrap();
         assert_eq!(game.next_turn, (player_0, BoardMark::X));
         assert_eq!(game.get_next_turn(), (player_1, BoardMark::O));
 
-        game.turn(&player_0, 0, 1);
+        game.turn(&player_0, 0, 1, 0).unwrap();
         assert_eq!(game.next_turn, (player_1, BoardMark::O));
         assert_eq!(game.get_next_turn(), (player_0, BoardMark::X));
 
-        game.turn(&player_1, 1, 1);
+        game.turn(&player_1, 1, 1, 0).unwrap();
         assert_eq!(game.next_turn, (player_0, BoardMark::X));
         assert_eq!(game.get_next_turn(), (player_1, BoardMark::O));
 
-        let turn_result = game.turn(&player_0, 0, 2);
+        let turn_result = game.turn(&player_0, 0, 2, 0).unwrap();
         assert_eq!(game.next_turn, (player_0, BoardMark::X));
         assert_eq!(game.get_next_turn(), (player_1, BoardMark::O));
 
@@ -363,45 +1265,128 @@ mod tests {
         );
     }
 
+    #[test]
+    fn success_turn_handle_game_round_misere() {
+        let (player_0, player_1, mut game) = setup_with_rules(Rules {
+            misere: true,
+            ..Default::default()
+        });
+
+        game.turn(&player_0, 0, 0, 0).unwrap();
+        game.turn(&player_1, 2, 2, 0).unwrap();
+        game.turn(&player_0, 0, 1, 0).unwrap();
+        game.turn(&player_1, 1, 2, 0).unwrap();
+
+        // Completed line loses
+        assert!(game.turn(&player_0, 0, 2, 0).unwrap());
+        assert_eq!(game.check_winner(), Some(BoardMark::O));
+        assert_eq!(game.get_winner(), Some(player_1));
+        assert!(game.get_view().misere);
+    }
+
+    #[test]
+    fn success_ultimate_turn() {
+        let (player_0, player_1, mut game) = setup_with_rules(Rules {
+            ultimate: true,
+            ..Default::default()
+        });
+
+        assert_eq!(
+            game.turn(&player_0, 0, 0, 0),
+            Err(TicTacToeError::UltimateTurnRequired)
+        );
+
+        // `player_0` takes right columns of the top sub-boards,
+        // while `player_1` sends them back there
+        let moves = [
+            (0, 2),
+            (2, 0),
+            (0, 5),
+            (5, 0),
+            (0, 8),
+            (8, 1),
+            (1, 2),
+            (2, 1),
+            (1, 5),
+            (5, 1),
+            (1, 8),
+            (8, 2),
+            (2, 5),
+            (5, 2),
+            (2, 8),
+            (8, 0),
+        ];
+        for (i, (board, cell)) in moves.into_iter().enumerate() {
+            let player = if i % 2 == 0 { &player_0 } else { &player_1 };
+            assert!(!game.ultimate_turn(player, board, cell, 0).unwrap());
+        }
+
+        assert_eq!(
+            game.board[0],
+            vec![Some(BoardMark::X), Some(BoardMark::X), None]
+        );
+        assert_eq!(game.moves[1].x, 6);
+
+        assert!(game.ultimate_turn(&player_0, 2, 2, 0).unwrap());
+        assert_eq!(game.get_winner(), Some(player_0));
+    }
+
+    #[test]
+    fn error_ultimate_turn() {
+        let (player_0, _, mut game) = setup();
+        assert_eq!(
+            game.ultimate_turn(&player_0, 0, 0, 0),
+            Err(TicTacToeError::NotUltimateGame)
+        );
+
+        let rules = Rules {
+            ultimate: true,
+            width: 9,
+            height: 9,
+            ..Default::default()
+        };
+        assert_eq!(rules.validate(), Err(TicTacToeError::InvalidUltimateRules));
+    }
+
     #[test]
     fn success_turn_handle_game_round_filled() {
         let (player_0, player_1, mut game) = setup();
         assert_eq!(game.next_turn, (player_0, BoardMark::X));
         assert_eq!(game.get_next_turn(), (player_1, BoardMark::O));
 
-        game.turn(&player_0, 1, 1);
+        game.turn(&player_0, 1, 1, 0).unwrap();
         assert_eq!(game.next_turn, (player_1, BoardMark::O));
         assert_eq!(game.get_next_turn(), (player_0, BoardMark::X));
 
-        game.turn(&player_1, 0, 0);
+        game.turn(&player_1, 0, 0, 0).unwrap();
         assert_eq!(game.next_turn, (player_0, BoardMark::X));
         assert_eq!(game.get_next_turn(), (player_1, BoardMark::O));
 
-        game.turn(&player_0, 2, 2);
+        game.turn(&player_0, 2, 2, 0).unwrap();
         assert_eq!(game.next_turn, (player_1, BoardMark::O));
         assert_eq!(game.get_next_turn(), (player_0, BoardMark::X));
 
-        game.turn(&player_1, 2, 1);
+        game.turn(&player_1, 2, 1, 0).unwrap();
         assert_eq!(game.next_turn, (player_0, BoardMark::X));
         assert_eq!(game.get_next_turn(), (player_1, BoardMark::O));
 
-        game.turn(&player_0, 2, 0);
+        game.turn(&player_0, 2, 0, 0).unwrap();
         assert_eq!(game.next_turn, (player_1, BoardMark::O));
         assert_eq!(game.get_next_turn(), (player_0, BoardMark::X));
 
-        game.turn(&player_1, 0, 2);
+        game.turn(&player_1, 0, 2, 0).unwrap();
         assert_eq!(game.next_turn, (player_0, BoardMark::X));
         assert_eq!(game.get_next_turn(), (player_1, BoardMark::O));
 
-        game.turn(&player_0, 0, 1);
+        game.turn(&player_0, 0, 1, 0).unwrap();
         assert_eq!(game.next_turn, (player_1, BoardMark::O));
         assert_eq!(game.get_next_turn(), (player_0, BoardMark::X));
 
-        game.turn(&player_1, 1, 0);
+        game.turn(&player_1, 1, 0, 0).unwrap();
         assert_eq!(game.next_turn, (player_0, BoardMark::X));
         assert_eq!(game.get_next_turn(), (player_1, BoardMark::O));
 
-        let turn_result = game.turn(&player_0, 1, 2);
+        let turn_result = game.turn(&player_0, 1, 2, 0).unwrap();
         assert_eq!(game.next_turn, (player_0, BoardMark::X));
         assert_eq!(game.get_next_turn(), (player_1, BoardMark::O));
 
@@ -412,11 +1397,44 @@ mod tests {
         assert_eq!(game.status, GameStatus::Finished { winner: None });
     }
 
+    #[test]
+    fn success_turn_handle_game_round_k_in_row() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let player_1 = ActorId::new([1u8; 32]);
+        let mut game = Game::init(player_0, Some(player_1), rules(15, 15, 5), 0, 0).unwrap();
+        game.accept(&player_1, 0, 0).unwrap();
+
+        for i in 0..4 {
+            assert!(!game.turn(&player_0, 10 - i, 3 + i, 0).unwrap());
+            assert!(!game.turn(&player_1, 0, i, 0).unwrap());
+        }
+
+        let turn_result = game.turn(&player_0, 6, 7, 0).unwrap();
+
+        assert!(turn_result);
+        assert!(game.is_ended());
+        assert_eq!(game.get_winner(), Some(player_0));
+    }
+
+    #[test]
+    fn success_turn_rectangular_board() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let player_1 = ActorId::new([1u8; 32]);
+        let mut game = Game::init(player_0, Some(player_1), rules(4, 2, 4), 0, 0).unwrap();
+        game.accept(&player_1, 0, 0).unwrap();
+
+        assert_eq!(game.board.len(), 2);
+        assert_eq!(game.board[0].len(), 4);
+
+        assert!(!game.turn(&player_0, 3, 1, 0).unwrap());
+        assert!(game.board[1][3].is_some());
+    }
+
     #[test]
     fn success_turn() {
         let (player_0, player_1, mut game) = setup();
 
-        let turn_result = game.turn(&player_0, 0, 0);
+        let turn_result = game.turn(&player_0, 0, 0, 0).unwrap();
 
         assert!(!turn_result);
         assert!(!game.is_ended());
@@ -425,11 +1443,65 @@ mod tests {
         assert_eq!(game.next_turn, (player_1, BoardMark::O));
     }
 
+    #[test]
+    fn success_turn_moves() {
+        let (player_0, player_1, mut game) = setup();
+
+        game.turn(&player_0, 2, 1, 10).unwrap();
+        game.turn(&player_1, 0, 0, 12).unwrap();
+
+        assert_eq!(
+            game.moves,
+            vec![
+                Move {
+                    player: player_0,
+                    x: 2,
+                    y: 1,
+                    mark: BoardMark::X,
+                    block: 10,
+                },
+                Move {
+                    player: player_1,
+                    x: 0,
+                    y: 0,
+                    mark: BoardMark::O,
+                    block: 12,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn success_get_view() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let mut game = Game::init(player_0, None, Rules::default(), 100, 0).unwrap();
+
+        let view = game.get_view();
+        assert_eq!(view.player_1, None);
+        assert_eq!(view.player_1_mark, BoardMark::O);
+        assert_eq!(view.status, GameStatus::Pending);
+
+        let player_1 = ActorId::new([1u8; 32]);
+        game.join(&player_1, 100, 0).unwrap();
+        game.turn(&player_0, 1, 2, 0).unwrap();
+
+        let view = game.get_view();
+        assert_eq!(view.board, game.board);
+        assert_eq!(view.board[2][1], Some(BoardMark::X));
+        assert_eq!(view.player_0, player_0);
+        assert_eq!(view.player_1, Some(player_1));
+        assert_eq!(view.player_0_mark, BoardMark::X);
+        assert_eq!(view.status, GameStatus::Created);
+        assert_eq!(view.next_turn, (player_1, BoardMark::O));
+        assert_eq!(view.moves_count, 1);
+        assert_eq!(view.stake, 100);
+    }
+
     #[test]
     fn success_cancel() {
         let (player_0, _, mut game) = setup();
 
-        game.cancel(&player_0);
+        game.cancel(&player_0).unwrap();
 
         assert!(game.is_ended());
         assert!(!game.is_board_filled());
@@ -437,60 +1509,660 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "You must have friends ;(")]
-    fn panic_init_players_eq() {
+    fn success_cancel_with_consent() {
+        let (player_0, player_1, mut game) = setup_wager(100);
+
+        game.turn(&player_0, 0, 0, 0).unwrap();
+
+        assert!(!game.cancel(&player_0).unwrap());
+        assert_eq!(game.cancel_offer, Some(player_0));
+        assert_eq!(
+            game.cancel(&player_0),
+            Err(TicTacToeError::CancelAlreadyOffered)
+        );
+
+        // Offer is withdrawn by the next move
+        game.turn(&player_1, 1, 1, 0).unwrap();
+        assert_eq!(game.cancel_offer, None);
+        assert!(!game.cancel(&player_0).unwrap());
+
+        assert!(game.cancel(&player_1).unwrap());
+        assert_eq!(game.status, GameStatus::Canceled);
+        assert_eq!(game.settle(), vec![(player_0, 100), (player_1, 100)]);
+    }
+
+    #[test]
+    fn success_resign() {
+        let (player_0, player_1, mut game) = setup_wager(100);
+
+        game.turn(&player_0, 0, 0, 0).unwrap();
+        game.resign(&player_0).unwrap();
+
+        assert_eq!(game.get_winner(), Some(player_1));
+        assert_eq!(game.settle(), vec![(player_1, 200)]);
+    }
+
+    #[test]
+    fn success_draw_offer() {
+        let (player_0, player_1, mut game) = setup();
+
+        game.offer_draw(&player_0).unwrap();
+        assert_eq!(
+            game.offer_draw(&player_1),
+            Err(TicTacToeError::DrawAlreadyOffered)
+        );
+        assert_eq!(
+            game.accept_draw(&player_0),
+            Err(TicTacToeError::NoDrawOffer)
+        );
+
+        game.decline_draw(&player_1).unwrap();
+        assert_eq!(game.draw_offer, None);
+
+        game.offer_draw(&player_1).unwrap();
+        game.turn(&player_0, 0, 0, 0).unwrap();
+        assert_eq!(game.draw_offer, Some(player_1));
+
+        game.accept_draw(&player_0).unwrap();
+        assert!(game.is_ended());
+        assert_eq!(game.status, GameStatus::Finished { winner: None });
+    }
+
+    #[test]
+    fn success_draw_offer_cleared_by_move() {
+        let (player_0, player_1, mut game) = setup();
+
+        game.offer_draw(&player_0).unwrap();
+        game.turn(&player_0, 0, 0, 0).unwrap();
+
+        assert_eq!(game.draw_offer, None);
+        assert_eq!(
+            game.accept_draw(&player_1),
+            Err(TicTacToeError::NoDrawOffer)
+        );
+    }
+
+    #[test]
+    fn success_takeback() {
+        let (player_0, player_1, mut game) = setup();
+
+        game.turn(&player_0, 0, 0, 0).unwrap();
+        game.turn(&player_1, 1, 1, 0).unwrap();
+
+        assert_eq!(
+            game.request_takeback(&player_0),
+            Err(TicTacToeError::NoMoveToTakeBack)
+        );
+        game.request_takeback(&player_1).unwrap();
+        assert_eq!(
+            game.approve_takeback(&player_1, 5),
+            Err(TicTacToeError::NoTakebackRequest)
+        );
+
+        let undone_move = game.approve_takeback(&player_0, 5).unwrap();
+
+        assert_eq!((undone_move.x, undone_move.y), (1, 1));
+        assert_eq!(game.board[1][1], None);
+        assert_eq!(game.next_turn, (player_1, BoardMark::O));
+        assert_eq!(game.moves.len(), 1);
+        assert_eq!(game.takeback_request, None);
+        assert_eq!(game.last_move_at, 5);
+
+        game.turn(&player_1, 2, 2, 6).unwrap();
+    }
+
+    #[test]
+    fn success_takeback_request_cleared_by_move() {
+        let (player_0, player_1, mut game) = setup();
+
+        game.turn(&player_0, 0, 0, 0).unwrap();
+        game.request_takeback(&player_0).unwrap();
+        game.turn(&player_1, 1, 1, 0).unwrap();
+
+        assert_eq!(
+            game.approve_takeback(&player_1, 0),
+            Err(TicTacToeError::NoTakebackRequest)
+        );
+    }
+
+    #[test]
+    fn success_settle_winner() {
+        let (player_0, player_1, mut game) = setup_wager(100);
+        assert_eq!(game.pot, 200);
+
+        game.turn(&player_0, 0, 0, 0).unwrap();
+        game.turn(&player_1, 1, 0, 0).unwrap();
+        game.turn(&player_0, 0, 1, 0).unwrap();
+        game.turn(&player_1, 1, 1, 0).unwrap();
+        game.turn(&player_0, 0, 2, 0).unwrap();
+
+        assert_eq!(game.settle(), vec![(player_0, 200)]);
+        assert_eq!(game.pot, 0);
+        assert!(game.settle().is_empty());
+    }
+
+    #[test]
+    fn success_settle_draw() {
+        let (player_0, player_1, mut game) = setup_wager(100);
+
+        game.status = GameStatus::Finished { winner: None };
+
+        assert_eq!(game.settle(), vec![(player_0, 100), (player_1, 100)]);
+    }
+
+    #[test]
+    fn success_settle_cancel_refund() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let player_1 = ActorId::new([1u8; 32]);
+        let mut game = Game::init(player_0, Some(player_1), Rules::default(), 100, 0).unwrap();
+
+        game.cancel(&player_1).unwrap();
+
+        assert_eq!(game.settle(), vec![(player_0, 100)]);
+    }
+
+    #[test]
+    fn success_decline() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let player_1 = ActorId::new([1u8; 32]);
+        let mut game = Game::init(player_0, Some(player_1), Rules::default(), 100, 0).unwrap();
+        assert!(game.is_pending());
+
+        game.decline(&player_1).unwrap();
+
+        assert!(game.is_ended());
+        assert_eq!(game.status, GameStatus::Canceled);
+        assert_eq!(game.settle(), vec![(player_0, 100)]);
+    }
+
+    #[test]
+    fn success_expire_invite() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let player_1 = ActorId::new([1u8; 32]);
+        let mut game = Game::init(player_0, Some(player_1), Rules::default(), 100, 10).unwrap();
+
+        assert!(!game.expire_invite(10 + INVITE_EXPIRATION_BLOCKS));
+
+        // Invite can't expire while its deposit is transferred
+        game.lock_deposit(&player_1).unwrap();
+        assert!(!game.expire_invite(11 + INVITE_EXPIRATION_BLOCKS));
+        game.unlock_deposit();
+
+        assert!(game.expire_invite(11 + INVITE_EXPIRATION_BLOCKS));
+        assert_eq!(game.status, GameStatus::Canceled);
+        assert_eq!(game.settle(), vec![(player_0, 100)]);
+        assert!(!game.expire_invite(11 + INVITE_EXPIRATION_BLOCKS));
+    }
+
+    #[test]
+    fn success_invite_expiration() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let player_1 = ActorId::new([1u8; 32]);
+        let mut game = Game::init(player_0, Some(player_1), Rules::default(), 0, 10).unwrap();
+
+        assert!(!game.is_invite_expired(10 + INVITE_EXPIRATION_BLOCKS));
+        assert!(game.is_invite_expired(11 + INVITE_EXPIRATION_BLOCKS));
+
+        game.accept(&player_1, 0, 10 + INVITE_EXPIRATION_BLOCKS)
+            .unwrap();
+        assert!(!game.is_invite_expired(11 + INVITE_EXPIRATION_BLOCKS));
+    }
+
+    #[test]
+    fn success_claim_timeout() {
+        let (player_0, _, mut game) = setup();
+
+        game.turn(&player_0, 0, 0, 10).unwrap();
+        assert!(!game.is_move_expired(10 + DEFAULT_MOVE_TIMEOUT));
+        assert!(game.is_move_expired(11 + DEFAULT_MOVE_TIMEOUT));
+
+        game.claim_timeout(&player_0, 11 + DEFAULT_MOVE_TIMEOUT)
+            .unwrap();
+
+        assert!(game.is_ended());
+        assert_eq!(game.get_winner(), Some(player_0));
+    }
+
+    #[test]
+    fn success_expire() {
+        let (player_0, player_1, mut game) = setup();
+
+        game.turn(&player_0, 0, 0, 10).unwrap();
+        assert!(!game.expire(10 + DEFAULT_MOVE_TIMEOUT));
+
+        game.turn(&player_1, 1, 1, 20).unwrap();
+        assert!(!game.expire(11 + DEFAULT_MOVE_TIMEOUT));
+        assert!(game.expire(21 + DEFAULT_MOVE_TIMEOUT));
+
+        assert_eq!(game.get_winner(), Some(player_1));
+        assert!(!game.expire(21 + DEFAULT_MOVE_TIMEOUT));
+    }
+
+    #[test]
+    fn success_coin_flip() {
+        let (player_0, player_1, mut game) = setup_coin_flip();
+        let (secret_0, secret_1) = ([2u8; 32], [3u8; 32]);
+
+        game.commit_flip(&player_0, commitment(&player_0, &secret_0), 1)
+            .unwrap();
+        game.commit_flip(&player_1, commitment(&player_1, &secret_1), 2)
+            .unwrap();
+
+        assert!(!game.reveal_flip(&player_0, secret_0, 3).unwrap());
+        assert!(game.reveal_flip(&player_1, secret_1, 4).unwrap());
+
+        assert!(game.coin_flip.is_none());
+        assert_eq!(game.next_turn, (player_1, BoardMark::X));
+        assert_eq!(game.get_board_mark(&player_0), BoardMark::O);
+        assert_eq!(game.last_move_at, 4);
+
+        assert!(!game.turn(&player_1, 1, 1, 5).unwrap());
+        assert_eq!(game.next_turn, (player_0, BoardMark::O));
+    }
+
+    #[test]
+    fn success_claim_flip_timeout() {
+        let (player_0, player_1, mut game) = setup_coin_flip();
+        let secret_1 = [3u8; 32];
+
+        game.commit_flip(&player_0, [0u8; 32], 10).unwrap();
+        game.commit_flip(&player_1, commitment(&player_1, &secret_1), 10)
+            .unwrap();
+        game.reveal_flip(&player_1, secret_1, 10).unwrap();
+
+        assert_eq!(
+            game.claim_flip_timeout(&player_0, 11 + DEFAULT_MOVE_TIMEOUT),
+            Err(TicTacToeError::CoinFlipNotAhead)
+        );
+        assert_eq!(
+            game.claim_flip_timeout(&player_1, 10 + DEFAULT_MOVE_TIMEOUT),
+            Err(TicTacToeError::MoveDeadlineNotPassed)
+        );
+
+        game.claim_flip_timeout(&player_1, 11 + DEFAULT_MOVE_TIMEOUT)
+            .unwrap();
+        assert_eq!(game.next_turn, (player_1, BoardMark::X));
+    }
+
+    #[test]
+    fn success_expire_flip() {
+        let (player_0, _, mut game) = setup_coin_flip();
+
+        assert!(!game.expire(11 + DEFAULT_MOVE_TIMEOUT));
+        assert!(!game.expire_flip(DEFAULT_MOVE_TIMEOUT));
+        assert!(game.expire_flip(1 + DEFAULT_MOVE_TIMEOUT));
+
+        assert!(!game.is_ended());
+        assert_eq!(game.next_turn, (player_0, BoardMark::X));
+    }
+
+    #[test]
+    fn success_join() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let player_1 = ActorId::new([1u8; 32]);
+        let mut game = Game::init(player_0, None, Rules::default(), 100, 0).unwrap();
+        assert!(game.is_open());
+        assert!(!game.is_invite_expired(u32::MAX));
+
+        game.join(&player_1, 100, 5).unwrap();
+
+        assert!(!game.is_open());
+        assert_eq!(game.status, GameStatus::Created);
+        assert_eq!(game.player_1, Some(player_1));
+        assert_eq!(game.get_board_mark(&player_1), BoardMark::O);
+        assert_eq!(game.pot, 200);
+
+        game.turn(&player_0, 0, 0, 6).unwrap();
+        assert_eq!(game.next_turn, (player_1, BoardMark::O));
+    }
+
+    #[test]
+    fn success_cancel_open_refund() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let mut game = Game::init(player_0, None, Rules::default(), 100, 0).unwrap();
+
+        game.cancel(&player_0).unwrap();
+
+        assert_eq!(game.settle(), vec![(player_0, 100)]);
+    }
+
+    #[test]
+    fn success_is_winning_cell() {
+        let (player_0, player_1, mut game) = setup();
+
+        game.turn(&player_0, 0, 2, 0).unwrap();
+        game.turn(&player_1, 0, 0, 0).unwrap();
+        game.turn(&player_0, 1, 1, 0).unwrap();
+        game.turn(&player_1, 1, 0, 0).unwrap();
+
+        assert!(!game.is_winning_cell(1, 1));
+        assert!(!game.is_winning_cell(2, 0));
+
+        game.turn(&player_0, 2, 0, 0).unwrap();
+
+        assert!(game.is_winning_cell(1, 1));
+        assert!(game.is_winning_cell(2, 0));
+        assert!(!game.is_winning_cell(0, 0));
+    }
+
+    #[test]
+    fn error_init_players_eq() {
+        let player_0 = ActorId::new([0u8; 32]);
+        assert_eq!(
+            Game::init(player_0, Some(player_0), Rules::default(), 0, 0).err(),
+            Some(TicTacToeError::SamePlayers)
+        );
+    }
+
+    #[test]
+    fn error_init_invalid_board_size() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let player_1 = ActorId::new([1u8; 32]);
+        assert_eq!(
+            Game::init(player_0, Some(player_1), rules(0, 3, 3), 0, 0).err(),
+            Some(TicTacToeError::InvalidBoardSize)
+        );
+    }
+
+    #[test]
+    fn error_init_invalid_win_length() {
         let player_0 = ActorId::new([0u8; 32]);
-        let _game = Game::init(player_0, player_0);
+        let player_1 = ActorId::new([1u8; 32]);
+        assert_eq!(
+            Game::init(player_0, Some(player_1), rules(3, 3, 4), 0, 0).err(),
+            Some(TicTacToeError::InvalidWinLength)
+        );
+    }
+
+    #[test]
+    fn error_turn_out_of_board() {
+        let (player_0, _, mut game) = setup();
+        assert_eq!(
+            game.turn(&player_0, 3, 0, 0),
+            Err(TicTacToeError::OutOfBoard)
+        );
     }
 
     #[test]
-    #[should_panic(expected = "Game is ended!")]
-    fn panic_turn_ended() {
+    fn error_turn_ended() {
         let (player_0, _, mut game) = setup();
 
-        game.cancel(&player_0);
-        game.turn(&player_0, 0, 0);
+        game.cancel(&player_0).unwrap();
+        assert_eq!(
+            game.turn(&player_0, 0, 0, 0),
+            Err(TicTacToeError::GameEnded)
+        );
     }
 
     #[test]
-    #[should_panic(expected = "Player not found in this game!")]
-    fn panic_turn_player_not_exists() {
+    fn error_turn_player_not_exists() {
         let (_, _, mut game) = setup();
         let player_2 = ActorId::new([2u8; 32]);
 
-        game.turn(&player_2, 0, 0);
+        assert_eq!(
+            game.turn(&player_2, 0, 0, 0),
+            Err(TicTacToeError::PlayerNotInGame)
+        );
     }
 
     #[test]
-    #[should_panic(expected = "It's not your turn!")]
-    fn panic_turn_invalid_sequence() {
+    fn error_turn_invalid_sequence() {
         let (_, player_1, mut game) = setup();
-        game.turn(&player_1, 0, 0);
+        assert_eq!(
+            game.turn(&player_1, 0, 0, 0),
+            Err(TicTacToeError::NotYourTurn)
+        );
     }
 
     #[test]
-    #[should_panic(expected = "Location is not empty!")]
-    fn panic_turn_location_not_empty() {
+    fn error_turn_location_not_empty() {
         let (player_0, player_1, mut game) = setup();
-        game.turn(&player_0, 0, 0);
-        game.turn(&player_1, 0, 0);
+        game.turn(&player_0, 0, 0, 0).unwrap();
+        assert_eq!(
+            game.turn(&player_1, 0, 0, 0),
+            Err(TicTacToeError::LocationNotEmpty)
+        );
     }
 
     #[test]
-    #[should_panic(expected = "Game is ended!")]
-    fn panic_cancel_ended() {
+    fn error_turn_not_started() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let player_1 = ActorId::new([1u8; 32]);
+        let mut game = Game::init(player_0, Some(player_1), Rules::default(), 100, 0).unwrap();
+
+        assert_eq!(
+            game.turn(&player_0, 0, 0, 0),
+            Err(TicTacToeError::GameNotStarted)
+        );
+    }
+
+    #[test]
+    fn error_accept_invalid_stake() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let player_1 = ActorId::new([1u8; 32]);
+        let mut game = Game::init(player_0, Some(player_1), Rules::default(), 100, 0).unwrap();
+
+        assert_eq!(
+            game.accept(&player_1, 50, 0),
+            Err(TicTacToeError::InvalidStake)
+        );
+    }
+
+    #[test]
+    fn success_watch() {
+        let (player_0, player_1, mut game) = setup();
+        let watcher = ActorId::new([2u8; 32]);
+
+        game.watch(&watcher).unwrap();
+        assert_eq!(
+            game.get_audience(),
+            BTreeSet::from([player_0, player_1, watcher])
+        );
+
+        game.unwatch(&watcher).unwrap();
+        assert_eq!(game.get_audience(), BTreeSet::from([player_0, player_1]));
+    }
+
+    #[test]
+    fn error_watch() {
         let (player_0, _, mut game) = setup();
+        let watcher = ActorId::new([2u8; 32]);
+
+        assert_eq!(game.unwatch(&watcher), Err(TicTacToeError::NotWatching));
+
+        game.watch(&watcher).unwrap();
+        assert_eq!(game.watch(&watcher), Err(TicTacToeError::AlreadyWatching));
+
+        for i in 1..MAX_WATCHERS {
+            game.watch(&ActorId::new([10 + i as u8; 32])).unwrap();
+        }
+        assert_eq!(
+            game.watch(&ActorId::new([100u8; 32])),
+            Err(TicTacToeError::TooManyWatchers)
+        );
+
+        assert_eq!(
+            game.kick_watcher(&watcher, &watcher),
+            Err(TicTacToeError::PlayerNotInGame)
+        );
+        game.kick_watcher(&player_0, &watcher).unwrap();
+        assert_eq!(game.watch(&watcher), Err(TicTacToeError::KickedWatcher));
+
+        game.resign(&player_0).unwrap();
+        assert_eq!(
+            game.watch(&ActorId::new([100u8; 32])),
+            Err(TicTacToeError::GameEnded)
+        );
+    }
+
+    #[test]
+    fn success_deposit_lock() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let player_1 = ActorId::new([1u8; 32]);
+        let mut game = Game::init(player_0, Some(player_1), Rules::default(), 100, 0).unwrap();
+        game.token = Some(ActorId::new([2u8; 32]));
+
+        game.lock_deposit(&player_1).unwrap();
+        assert_eq!(
+            game.decline(&player_1),
+            Err(TicTacToeError::DepositInProgress)
+        );
+        assert_eq!(
+            game.cancel(&player_0),
+            Err(TicTacToeError::DepositInProgress)
+        );
+
+        game.unlock_deposit();
+        game.accept(&player_1, 100, 0).unwrap();
+        assert_eq!(game.pot, 200);
+    }
+
+    #[test]
+    fn error_join_deposit_in_progress() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let player_1 = ActorId::new([1u8; 32]);
+        let mut game = Game::init(player_0, None, Rules::default(), 100, 0).unwrap();
+
+        game.lock_deposit(&player_1).unwrap();
+        assert_eq!(
+            game.lock_deposit(&ActorId::new([2u8; 32])),
+            Err(TicTacToeError::DepositInProgress)
+        );
+        assert_eq!(
+            game.join(&ActorId::new([2u8; 32]), 100, 0),
+            Err(TicTacToeError::DepositInProgress)
+        );
+    }
+
+    #[test]
+    fn error_accept_not_opponent() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let player_1 = ActorId::new([1u8; 32]);
+        let mut game = Game::init(player_0, Some(player_1), Rules::default(), 0, 0).unwrap();
+
+        assert_eq!(
+            game.accept(&player_0, 0, 0),
+            Err(TicTacToeError::InviteNotAddressedToYou)
+        );
+    }
+
+    #[test]
+    fn error_accept_expired() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let player_1 = ActorId::new([1u8; 32]);
+        let mut game = Game::init(player_0, Some(player_1), Rules::default(), 0, 10).unwrap();
+
+        assert_eq!(
+            game.accept(&player_1, 0, 11 + INVITE_EXPIRATION_BLOCKS),
+            Err(TicTacToeError::InviteExpired)
+        );
+    }
 
-        game.cancel(&player_0);
-        game.cancel(&player_0);
+    #[test]
+    fn error_decline_accepted() {
+        let (_, player_1, mut game) = setup();
+        assert_eq!(game.decline(&player_1), Err(TicTacToeError::GameNotPending));
+    }
+
+    #[test]
+    fn error_claim_timeout_not_passed() {
+        let (player_0, _, mut game) = setup();
+
+        game.turn(&player_0, 0, 0, 10).unwrap();
+        assert_eq!(
+            game.claim_timeout(&player_0, 10 + DEFAULT_MOVE_TIMEOUT),
+            Err(TicTacToeError::MoveDeadlineNotPassed)
+        );
+    }
+
+    #[test]
+    fn error_claim_timeout_own_turn() {
+        let (player_0, player_1, mut game) = setup();
+
+        game.turn(&player_0, 0, 0, 10).unwrap();
+        assert_eq!(
+            game.claim_timeout(&player_1, 11 + DEFAULT_MOVE_TIMEOUT),
+            Err(TicTacToeError::YourTurn)
+        );
+    }
+
+    #[test]
+    fn error_init_invalid_move_timeout() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let player_1 = ActorId::new([1u8; 32]);
+        let rules = Rules {
+            move_timeout: 0,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            Game::init(player_0, Some(player_1), rules, 0, 0).err(),
+            Some(TicTacToeError::InvalidMoveTimeout)
+        );
+    }
+
+    #[test]
+    fn error_join_own_game() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let mut game = Game::init(player_0, None, Rules::default(), 0, 0).unwrap();
+
+        assert_eq!(game.join(&player_0, 0, 0), Err(TicTacToeError::SamePlayers));
+    }
+
+    #[test]
+    fn error_join_invite() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let player_1 = ActorId::new([1u8; 32]);
+        let player_2 = ActorId::new([2u8; 32]);
+        let mut game = Game::init(player_0, Some(player_1), Rules::default(), 0, 0).unwrap();
+
+        assert_eq!(game.join(&player_2, 0, 0), Err(TicTacToeError::GameNotOpen));
+    }
+
+    #[test]
+    fn error_cancel_ended() {
+        let (player_0, _, mut game) = setup();
+
+        game.cancel(&player_0).unwrap();
+        assert_eq!(game.cancel(&player_0), Err(TicTacToeError::GameEnded));
+    }
+
+    #[test]
+    fn error_cancel_tournament_game() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let player_1 = ActorId::new([1u8; 32]);
+        let mut game =
+            Game::init_for_tournament(player_0, player_1, Rules::default(), 1, 0).unwrap();
+        assert_eq!(game.status, GameStatus::Created);
+
+        assert_eq!(
+            game.cancel(&player_1),
+            Err(TicTacToeError::TournamentGameCantBeCanceled)
+        );
+    }
+
+    #[test]
+    fn error_resign_pending() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let mut game = Game::init(player_0, None, Rules::default(), 0, 0).unwrap();
+
+        assert_eq!(game.resign(&player_0), Err(TicTacToeError::GameNotStarted));
+    }
+
+    #[test]
+    fn error_turn_coin_flip_not_finished() {
+        let (player_0, _, mut game) = setup_coin_flip();
+        assert_eq!(
+            game.turn(&player_0, 0, 0, 0),
+            Err(TicTacToeError::CoinFlipNotFinished)
+        );
     }
 
     #[test]
-    #[should_panic(expected = "Player not found in this game!")]
-    fn panic_cancel_player_not_exists() {
+    fn error_cancel_player_not_exists() {
         let (_, _, mut game) = setup();
         let player_2 = ActorId::new([2u8; 32]);
 
-        game.cancel(&player_2);
+        assert_eq!(game.cancel(&player_2), Err(TicTacToeError::PlayerNotInGame));
     }
 }
@@ -1,10 +1,40 @@
-use crate::state::{BoardMark, GameID};
+use crate::{
+    config::Config,
+    rating::{LeaderboardBy, LeaderboardEntry},
+    state::{ArchivedGame, BoardMark, GameID, GameStatus, GameView, Move},
+    tournament::{Match, Tournament, TournamentID},
+};
 use codec::{Decode, Encode};
 use gstd::{prelude::*, ActorId};
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum StatusFilter {
+    Pending,
+    Active,
+    Canceled,
+    Finished,
+}
+
+impl StatusFilter {
+    pub fn matches(&self, status: &GameStatus) -> bool {
+        matches!(
+            (self, status),
+            (StatusFilter::Pending, GameStatus::Pending)
+                | (StatusFilter::Active, GameStatus::Created)
+                | (StatusFilter::Canceled, GameStatus::Canceled)
+                | (StatusFilter::Finished, GameStatus::Finished { winner: _ })
+        )
+    }
+}
+
 #[derive(Debug, Encode, Decode, TypeInfo)]
 pub enum StateQuery {
     GetNonce,
+    GetConfig,
+    GetFees,
+    GetTokenFees(ActorId),
+    /// Token payouts of the player, which can be claimed.
+    GetUnpaidPayouts(ActorId),
     GetGamesLen,
     IsEnded(GameID),
     IsBoardFilled(GameID),
@@ -12,11 +42,41 @@ pub enum StateQuery {
     GetPlayer((GameID, BoardMark)),
     GetNextTurn(GameID),
     GetWinner(GameID),
+    GetInvites(ActorId),
+    GetOpenGames,
+    GetMoves(GameID),
+    GetGamesByPlayer {
+        player: ActorId,
+        status_filter: Option<StatusFilter>,
+        offset: u64,
+        limit: u64,
+    },
+    GetGame(GameID),
+    GetRating(ActorId),
+    GetLeaderboard {
+        by: LeaderboardBy,
+        offset: u64,
+        limit: u64,
+    },
+    GetArchivedGame(GameID),
+    GetTournament(TournamentID),
+    GetBracket(TournamentID),
 }
 
 #[derive(Debug, Encode, Decode, TypeInfo)]
 pub enum StateQueryReply {
     Nonce(GameID),
+    Config {
+        owner: ActorId,
+        config: Config,
+        paused: bool,
+    },
+    Fees {
+        collected: u128,
+        balance: u128,
+    },
+    /// Token program with unpaid amount.
+    UnpaidPayouts(Vec<(ActorId, u128)>),
     GamesLen(GameID),
     IsEnded(bool),
     IsBoardFilled(bool),
@@ -27,4 +87,15 @@ pub enum StateQueryReply {
         board_mark: BoardMark,
     },
     Winner(Option<ActorId>),
+    Invites(Vec<GameID>),
+    OpenGames(Vec<GameID>),
+    Moves(Vec<Move>),
+    Games(Vec<GameID>),
+    /// Boxed, since the view is much larger than other replies.
+    Game(Box<GameView>),
+    Rating(u32),
+    Leaderboard(Vec<LeaderboardEntry>),
+    ArchivedGame(ArchivedGame),
+    Tournament(Tournament),
+    Bracket(Vec<Vec<Match>>),
 }
@@ -0,0 +1,404 @@
+use crate::{
+    error::TicTacToeError,
+    state::{GameID, Rules},
+};
+use codec::{Decode, Encode};
+use gstd::{prelude::*, ActorId};
+
+pub type TournamentID = u128;
+
+/// Largest bracket, whose first round games fit into one message.
+pub const MAX_TOURNAMENT_PLAYERS: u32 = 32;
+
+/// Number of replays of a tied match before it's decided by seeding.
+pub const MAX_REPLAYS: u32 = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum TournamentStatus {
+    Registration,
+    InProgress,
+    Finished { winner: ActorId },
+}
+
+/// Single bracket match, which is replayed with swapped sides on a tie.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct Match {
+    pub player_0: ActorId,
+    /// `None` means `player_0` advances without a game.
+    pub player_1: Option<ActorId>,
+    pub game: Option<GameID>,
+    pub winner: Option<ActorId>,
+    pub replays: u32,
+}
+
+/// Match index in the current round with players of a game which should be opened.
+pub type Pairing = (usize, ActorId, ActorId);
+
+/// Single-elimination tournament.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct Tournament {
+    pub creator: ActorId,
+    pub rules: Rules,
+    pub max_players: u32,
+    pub players: Vec<ActorId>,
+    pub rounds: Vec<Vec<Match>>,
+    pub status: TournamentStatus,
+}
+
+impl Tournament {
+    pub fn init(creator: ActorId, rules: Rules, max_players: u32) -> Result<Self, TicTacToeError> {
+        rules.validate()?;
+
+        if max_players < 2 {
+            return Err(TicTacToeError::InvalidMaxPlayers);
+        }
+
+        if max_players > MAX_TOURNAMENT_PLAYERS {
+            return Err(TicTacToeError::TooManyTournamentPlayers);
+        }
+
+        Ok(Tournament {
+            creator,
+            rules,
+            max_players,
+            players: Vec::new(),
+            rounds: Vec::new(),
+            status: TournamentStatus::Registration,
+        })
+    }
+
+    pub fn register(&mut self, player: &ActorId) -> Result<(), TicTacToeError> {
+        self.assert_registration()?;
+
+        if self.players.contains(player) {
+            return Err(TicTacToeError::AlreadyRegistered);
+        }
+
+        if self.players.len() >= self.max_players as usize {
+            return Err(TicTacToeError::TournamentFull);
+        }
+
+        self.players.push(*player);
+        Ok(())
+    }
+
+    /// Handle tournament start by its creator.
+    ///
+    /// Returns pairings of the first round.
+    pub fn start(&mut self, player: &ActorId) -> Result<Vec<Pairing>, TicTacToeError> {
+        self.assert_registration()?;
+
+        if player != &self.creator {
+            return Err(TicTacToeError::NotTournamentCreator);
+        }
+
+        if self.players.len() < 2 {
+            return Err(TicTacToeError::NotEnoughPlayers);
+        }
+
+        self.status = TournamentStatus::InProgress;
+        Ok(self.pair(self.players.clone()))
+    }
+
+    /// Links opened game to the match of the current round.
+    pub fn set_game(&mut self, match_index: usize, game: GameID) {
+        let round = self.rounds.last_mut().expect("Tournament is not started!");
+        round[match_index].game = Some(game);
+    }
+
+    /// Handle finished tournament `game` with `winner`.
+    ///
+    /// Returns pairings of games which should be opened:
+    ///
+    /// - Replay with swapped sides on a tie, up to `MAX_REPLAYS` times,
+    ///   then the match goes to the player registered first.
+    ///
+    /// - Next round when all matches of the current round are played.
+    pub fn handle_result(&mut self, game: GameID, winner: Option<ActorId>) -> Vec<Pairing> {
+        let round = match self.rounds.last_mut() {
+            Some(round) => round,
+            None => return Vec::new(),
+        };
+
+        let (match_index, current_match) = match round
+            .iter_mut()
+            .enumerate()
+            .find(|(_, current_match)| current_match.game == Some(game))
+        {
+            Some(found) => found,
+            None => return Vec::new(),
+        };
+
+        let winner = match winner {
+            Some(winner) => winner,
+            None if current_match.replays < MAX_REPLAYS => {
+                let player_0 = current_match.player_1.expect("Invalid data");
+                let player_1 = current_match.player_0;
+
+                current_match.player_0 = player_0;
+                current_match.player_1 = Some(player_1);
+                current_match.game = None;
+                current_match.replays += 1;
+
+                return vec![(match_index, player_0, player_1)];
+            }
+            None => {
+                let player_1 = current_match.player_1.expect("Invalid data");
+                let seed = |player| self.players.iter().position(|p| p == player);
+
+                if seed(&current_match.player_0) < seed(&player_1) {
+                    current_match.player_0
+                } else {
+                    player_1
+                }
+            }
+        };
+
+        current_match.winner = Some(winner);
+
+        if !round
+            .iter()
+            .all(|current_match| current_match.winner.is_some())
+        {
+            return Vec::new();
+        }
+
+        let winners: Vec<ActorId> = round
+            .iter()
+            .filter_map(|current_match| current_match.winner)
+            .collect();
+
+        if let [winner] = winners[..] {
+            self.status = TournamentStatus::Finished { winner };
+            return Vec::new();
+        }
+
+        self.pair(winners)
+    }
+
+    pub fn get_winner(&self) -> Option<ActorId> {
+        match self.status {
+            TournamentStatus::Finished { winner } => Some(winner),
+            _ => None,
+        }
+    }
+
+    /// Adds new round where `players` are paired in order.
+    ///
+    /// The last players advance without a game, so the next round
+    /// has a power of two players and no more byes.
+    fn pair(&mut self, players: Vec<ActorId>) -> Vec<Pairing> {
+        let byes = players.len().next_power_of_two() - players.len();
+        let (paired, advanced) = players.split_at(players.len() - byes);
+
+        let mut round = Vec::new();
+        let mut pairings = Vec::new();
+
+        for (match_index, pair) in paired.chunks_exact(2).enumerate() {
+            let (player_0, player_1) = (pair[0], pair[1]);
+
+            round.push(Match {
+                player_0,
+                player_1: Some(player_1),
+                game: None,
+                winner: None,
+                replays: 0,
+            });
+            pairings.push((match_index, player_0, player_1));
+        }
+
+        for &player_0 in advanced {
+            round.push(Match {
+                player_0,
+                player_1: None,
+                game: None,
+                winner: Some(player_0),
+                replays: 0,
+            });
+        }
+
+        self.rounds.push(round);
+        pairings
+    }
+
+    fn assert_registration(&self) -> Result<(), TicTacToeError> {
+        if self.status != TournamentStatus::Registration {
+            return Err(TicTacToeError::RegistrationClosed);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup(players_len: u8) -> (Vec<ActorId>, Tournament) {
+        let creator = ActorId::new([100u8; 32]);
+        let players: Vec<ActorId> = (0..players_len).map(|i| ActorId::new([i; 32])).collect();
+
+        let mut tournament = Tournament::init(creator, Rules::default(), 8).unwrap();
+        for player in &players {
+            tournament.register(player).unwrap();
+        }
+
+        (players, tournament)
+    }
+
+    /// Links each pairing to a game id starting from `first_game`.
+    fn open_games(tournament: &mut Tournament, pairings: &[Pairing], first_game: GameID) {
+        for (i, (match_index, _, _)) in pairings.iter().enumerate() {
+            tournament.set_game(*match_index, first_game + i as GameID);
+        }
+    }
+
+    #[test]
+    fn success_tournament() {
+        let (players, mut tournament) = setup(3);
+
+        let pairings = tournament.start(&tournament.creator.clone()).unwrap();
+        assert_eq!(pairings, vec![(0, players[0], players[1])]);
+        assert_eq!(tournament.status, TournamentStatus::InProgress);
+        assert_eq!(tournament.rounds[0][1].winner, Some(players[2]));
+        open_games(&mut tournament, &pairings, 1);
+
+        // Tie is replayed with swapped sides
+        let pairings = tournament.handle_result(1, None);
+        assert_eq!(pairings, vec![(0, players[1], players[0])]);
+        open_games(&mut tournament, &pairings, 2);
+
+        let pairings = tournament.handle_result(2, Some(players[0]));
+        assert_eq!(pairings, vec![(0, players[0], players[2])]);
+        assert_eq!(tournament.rounds.len(), 2);
+        open_games(&mut tournament, &pairings, 3);
+
+        assert!(tournament.handle_result(1, Some(players[1])).is_empty());
+        assert!(tournament.handle_result(3, Some(players[2])).is_empty());
+        assert_eq!(
+            tournament.status,
+            TournamentStatus::Finished { winner: players[2] }
+        );
+        assert_eq!(tournament.get_winner(), Some(players[2]));
+    }
+
+    #[test]
+    fn success_round_waits_for_all_matches() {
+        let (players, mut tournament) = setup(4);
+
+        let pairings = tournament.start(&tournament.creator.clone()).unwrap();
+        assert_eq!(pairings.len(), 2);
+        open_games(&mut tournament, &pairings, 1);
+
+        assert!(tournament.handle_result(1, Some(players[1])).is_empty());
+
+        let pairings = tournament.handle_result(2, Some(players[2]));
+        assert_eq!(pairings, vec![(0, players[1], players[2])]);
+    }
+
+    #[test]
+    fn success_byes_in_first_round() {
+        let (players, mut tournament) = setup(5);
+
+        let pairings = tournament.start(&tournament.creator.clone()).unwrap();
+        assert_eq!(pairings, vec![(0, players[0], players[1])]);
+        assert_eq!(tournament.rounds[0].len(), 4);
+        open_games(&mut tournament, &pairings, 1);
+
+        let pairings = tournament.handle_result(1, Some(players[1]));
+        assert_eq!(
+            pairings,
+            vec![(0, players[1], players[2]), (1, players[3], players[4])]
+        );
+        open_games(&mut tournament, &pairings, 2);
+
+        assert!(tournament.handle_result(3, Some(players[4])).is_empty());
+        let pairings = tournament.handle_result(2, Some(players[1]));
+        assert_eq!(pairings, vec![(0, players[1], players[4])]);
+        assert!(tournament.rounds[1..]
+            .iter()
+            .flatten()
+            .all(|current_match| current_match.player_1.is_some()));
+    }
+
+    #[test]
+    fn success_tie_break_after_replays() {
+        let (players, mut tournament) = setup(2);
+
+        let pairings = tournament.start(&tournament.creator.clone()).unwrap();
+        open_games(&mut tournament, &pairings, 1);
+
+        for game in 1..=MAX_REPLAYS as GameID {
+            let pairings = tournament.handle_result(game, None);
+            assert_eq!(pairings.len(), 1);
+            open_games(&mut tournament, &pairings, game + 1);
+        }
+
+        // Match goes to the player registered first
+        let last_game = MAX_REPLAYS as GameID + 1;
+        assert!(tournament.handle_result(last_game, None).is_empty());
+        assert_eq!(tournament.get_winner(), Some(players[0]));
+    }
+
+    #[test]
+    fn error_init() {
+        let creator = ActorId::new([100u8; 32]);
+
+        assert_eq!(
+            Tournament::init(creator, Rules::default(), 1).err(),
+            Some(TicTacToeError::InvalidMaxPlayers)
+        );
+        assert!(Tournament::init(creator, Rules::default(), MAX_TOURNAMENT_PLAYERS).is_ok());
+        assert_eq!(
+            Tournament::init(creator, Rules::default(), MAX_TOURNAMENT_PLAYERS + 1).err(),
+            Some(TicTacToeError::TooManyTournamentPlayers)
+        );
+    }
+
+    #[test]
+    fn error_register_started() {
+        let (_, mut tournament) = setup(2);
+
+        tournament.start(&tournament.creator.clone()).unwrap();
+        assert_eq!(
+            tournament.register(&ActorId::new([10u8; 32])),
+            Err(TicTacToeError::RegistrationClosed)
+        );
+    }
+
+    #[test]
+    fn error_register_twice() {
+        let (players, mut tournament) = setup(2);
+        assert_eq!(
+            tournament.register(&players[0]),
+            Err(TicTacToeError::AlreadyRegistered)
+        );
+    }
+
+    #[test]
+    fn error_register_full() {
+        let (_, mut tournament) = setup(8);
+        assert_eq!(
+            tournament.register(&ActorId::new([10u8; 32])),
+            Err(TicTacToeError::TournamentFull)
+        );
+    }
+
+    #[test]
+    fn error_start_not_creator() {
+        let (players, mut tournament) = setup(2);
+        assert_eq!(
+            tournament.start(&players[0]),
+            Err(TicTacToeError::NotTournamentCreator)
+        );
+    }
+
+    #[test]
+    fn error_start_not_enough_players() {
+        let (_, mut tournament) = setup(1);
+        assert_eq!(
+            tournament.start(&tournament.creator.clone()),
+            Err(TicTacToeError::NotEnoughPlayers)
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use crate::state::{BoardMark, Game, GameID};
+use codec::{Decode, Encode};
+use gstd::{prelude::*, ActorId};
+
+/// Proof of `winner` victory in game `id`, stored as the trophy token metadata.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct TrophyMetadata {
+    pub id: GameID,
+    pub player_0: ActorId,
+    pub player_1: ActorId,
+    pub winner: ActorId,
+    /// Final board, which holds winners of sub-boards in ultimate game.
+    pub board: Vec<Vec<Option<BoardMark>>>,
+    /// Cells of ultimate game sub-boards, see `UltimateBoard::cells`.
+    pub ultimate_cells: Option<Vec<Vec<Option<BoardMark>>>>,
+    pub ended_at: u32,
+}
+
+impl TrophyMetadata {
+    /// Returns trophy of game `id` ended at `ended_at` block, if it's won.
+    ///
+    /// Practice games against the bot are not rewarded.
+    pub fn new(id: GameID, game: &Game, ended_at: u32) -> Option<Self> {
+        if game.bot.is_some() {
+            return None;
+        }
+
+        Some(TrophyMetadata {
+            id,
+            player_0: game.player_0,
+            player_1: game.player_1.expect("Invalid data"),
+            winner: game.get_winner()?,
+            board: game.board.clone(),
+            ultimate_cells: game
+                .ultimate
+                .as_ref()
+                .map(|ultimate| ultimate.cells.clone()),
+            ended_at,
+        })
+    }
+}
+
+/// Action of the trophy NFT program.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum NFTAction {
+    Mint {
+        to: ActorId,
+        token_metadata: TrophyMetadata,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bot::Difficulty, state::Rules};
+
+    #[test]
+    fn success_trophy() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let player_1 = ActorId::new([1u8; 32]);
+        let mut game = Game::init(player_0, Some(player_1), Rules::default(), 0, 0).unwrap();
+        game.accept(&player_1, 0, 0).unwrap();
+        game.resign(&player_0).unwrap();
+
+        let trophy = TrophyMetadata::new(1, &game, 10).unwrap();
+        assert_eq!(trophy.winner, player_1);
+        assert_eq!(trophy.board, game.board);
+        assert_eq!(trophy.ultimate_cells, None);
+        assert_eq!(trophy.ended_at, 10);
+    }
+
+    #[test]
+    fn success_ultimate_trophy() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let player_1 = ActorId::new([1u8; 32]);
+        let rules = Rules {
+            ultimate: true,
+            ..Default::default()
+        };
+        let mut game = Game::init(player_0, Some(player_1), rules, 0, 0).unwrap();
+        game.accept(&player_1, 0, 0).unwrap();
+        game.ultimate_turn(&player_0, 4, 4, 0).unwrap();
+        game.resign(&player_1).unwrap();
+
+        let trophy = TrophyMetadata::new(1, &game, 10).unwrap();
+        assert_eq!(trophy.winner, player_0);
+        assert_eq!(trophy.ultimate_cells.unwrap()[4][4], Some(BoardMark::X));
+    }
+
+    #[test]
+    fn success_no_trophy() {
+        let player_0 = ActorId::new([0u8; 32]);
+        let bot = ActorId::new([1u8; 32]);
+        let mut game =
+            Game::init_vs_bot(player_0, bot, Difficulty::Easy, Rules::default(), 0).unwrap();
+        assert_eq!(TrophyMetadata::new(1, &game, 10), None);
+
+        game.resign(&bot).unwrap();
+        assert_eq!(game.get_winner(), Some(player_0));
+        assert_eq!(TrophyMetadata::new(1, &game, 10), None);
+    }
+}
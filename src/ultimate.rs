@@ -0,0 +1,161 @@
+use crate::{error::TicTacToeError, state::BoardMark};
+use codec::{Decode, Encode};
+use gstd::prelude::*;
+
+/// Number of sub-boards, which is also the number of cells in each of them.
+pub const SUB_BOARDS: usize = 9;
+
+/// Cell indices of each line on a 3x3 board.
+const LINES: [[usize; 3]; 8] = [
+    [0, 1, 2],
+    [3, 4, 5],
+    [6, 7, 8],
+    [0, 3, 6],
+    [1, 4, 7],
+    [2, 5, 8],
+    [0, 4, 8],
+    [2, 4, 6],
+];
+
+/// Nine 3x3 sub-boards of ultimate tic-tac-toe, where won sub-boards
+/// are the marks of `Game::board`.
+///
+/// Sub-boards and their cells are indexed in row-major order.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct UltimateBoard {
+    /// Cells indexed by `[board][cell]`.
+    pub cells: Vec<Vec<Option<BoardMark>>>,
+    /// Mark which completed a line on each sub-board.
+    pub winners: Vec<Option<BoardMark>>,
+    /// Sub-board of the next move, any open one if `None`.
+    pub next_board: Option<u8>,
+}
+
+impl Default for UltimateBoard {
+    fn default() -> Self {
+        UltimateBoard {
+            cells: vec![vec![None; SUB_BOARDS]; SUB_BOARDS],
+            winners: vec![None; SUB_BOARDS],
+            next_board: None,
+        }
+    }
+}
+
+impl UltimateBoard {
+    /// Returns `true` if `board` is won or filled.
+    pub fn is_closed(&self, board: usize) -> bool {
+        self.winners[board].is_some() || self.cells[board].iter().all(Option::is_some)
+    }
+
+    pub fn is_filled(&self) -> bool {
+        (0..SUB_BOARDS).all(|board| self.is_closed(board))
+    }
+
+    /// Places `mark` to `cell` of `board` and sends the opponent to the sub-board
+    /// matching `cell`, or to any open one if it's closed.
+    ///
+    /// Returns `true` if `mark` has won the sub-board.
+    pub fn place(
+        &mut self,
+        board: usize,
+        cell: usize,
+        mark: &BoardMark,
+    ) -> Result<bool, TicTacToeError> {
+        if board >= SUB_BOARDS || cell >= SUB_BOARDS {
+            return Err(TicTacToeError::OutOfBoard);
+        }
+
+        if self.is_closed(board) || matches!(self.next_board, Some(next) if next as usize != board)
+        {
+            return Err(TicTacToeError::InvalidSubBoard);
+        }
+
+        if self.cells[board][cell].is_some() {
+            return Err(TicTacToeError::LocationNotEmpty);
+        }
+
+        self.cells[board][cell] = Some(mark.clone());
+
+        let is_won = LINES.iter().any(|line| {
+            line.iter()
+                .all(|&index| self.cells[board][index].as_ref() == Some(mark))
+        });
+        if is_won {
+            self.winners[board] = Some(mark.clone());
+        }
+
+        self.next_board = if self.is_closed(cell) {
+            None
+        } else {
+            Some(cell as u8)
+        };
+
+        Ok(is_won)
+    }
+
+    /// Returns (`x`, `y`) coordinates of `cell` of `board` on the whole 9x9 grid.
+    pub fn to_grid(board: usize, cell: usize) -> (usize, usize) {
+        (board % 3 * 3 + cell % 3, board / 3 * 3 + cell / 3)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn success_place() {
+        let mut ultimate = UltimateBoard::default();
+
+        assert!(!ultimate.place(4, 0, &BoardMark::X).unwrap());
+        assert_eq!(ultimate.next_board, Some(0));
+
+        ultimate.place(0, 4, &BoardMark::O).unwrap();
+        ultimate.place(4, 1, &BoardMark::X).unwrap();
+        ultimate.place(1, 4, &BoardMark::O).unwrap();
+
+        assert!(ultimate.place(4, 2, &BoardMark::X).unwrap());
+        assert_eq!(ultimate.winners[4], Some(BoardMark::X));
+        assert!(ultimate.is_closed(4));
+
+        // Closed sub-board is played no more
+        ultimate.place(2, 4, &BoardMark::O).unwrap();
+        assert_eq!(ultimate.next_board, None);
+        assert_eq!(
+            ultimate.place(4, 8, &BoardMark::X),
+            Err(TicTacToeError::InvalidSubBoard)
+        );
+        ultimate.place(8, 8, &BoardMark::X).unwrap();
+        assert!(!ultimate.is_filled());
+    }
+
+    #[test]
+    fn success_to_grid() {
+        assert_eq!(UltimateBoard::to_grid(0, 0), (0, 0));
+        assert_eq!(UltimateBoard::to_grid(4, 4), (4, 4));
+        assert_eq!(UltimateBoard::to_grid(5, 7), (7, 5));
+        assert_eq!(UltimateBoard::to_grid(8, 8), (8, 8));
+    }
+
+    #[test]
+    fn error_place() {
+        let mut ultimate = UltimateBoard::default();
+
+        assert_eq!(
+            ultimate.place(9, 0, &BoardMark::X),
+            Err(TicTacToeError::OutOfBoard)
+        );
+
+        ultimate.place(0, 1, &BoardMark::X).unwrap();
+        assert_eq!(
+            ultimate.place(2, 0, &BoardMark::O),
+            Err(TicTacToeError::InvalidSubBoard)
+        );
+
+        ultimate.place(1, 0, &BoardMark::O).unwrap();
+        assert_eq!(
+            ultimate.place(0, 1, &BoardMark::X),
+            Err(TicTacToeError::LocationNotEmpty)
+        );
+    }
+}